@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS users_username_idx;
+
+CREATE UNIQUE INDEX IF NOT EXISTS users_username_lower_idx ON users (lower(username));
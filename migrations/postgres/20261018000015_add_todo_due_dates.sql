@@ -0,0 +1,3 @@
+ALTER TABLE todos ADD COLUMN due_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS todos_user_id_due_at_idx ON todos (user_id, due_at, id);
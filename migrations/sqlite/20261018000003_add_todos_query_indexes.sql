@@ -0,0 +1,4 @@
+CREATE INDEX IF NOT EXISTS todos_user_id_created_at_idx ON todos (user_id, created_at, id);
+CREATE INDEX IF NOT EXISTS todos_user_id_updated_at_idx ON todos (user_id, updated_at, id);
+CREATE INDEX IF NOT EXISTS todos_user_id_title_idx ON todos (user_id, title, id);
+CREATE INDEX IF NOT EXISTS todos_user_id_completed_idx ON todos (user_id, completed);
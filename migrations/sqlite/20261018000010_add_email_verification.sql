@@ -0,0 +1,5 @@
+ALTER TABLE users ADD COLUMN email TEXT;
+ALTER TABLE users ADD COLUMN verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Accounts created before verification existed were already active.
+UPDATE users SET verified = TRUE;
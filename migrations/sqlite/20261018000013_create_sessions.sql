@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    user_agent TEXT,
+    ip_address TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    last_used_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS sessions_user_id_idx ON sessions (user_id);
+
+-- Sessions started before this migration have no device details.
+INSERT INTO sessions (id, user_id, user_agent, ip_address, created_at, last_used_at)
+SELECT family_id, user_id, NULL, 'unknown', MIN(created_at), MAX(created_at)
+FROM refresh_tokens
+GROUP BY family_id, user_id;
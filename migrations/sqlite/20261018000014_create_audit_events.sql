@@ -0,0 +1,25 @@
+-- No foreign keys: events must outlive the accounts they mention.
+CREATE TABLE IF NOT EXISTS audit_events (
+    id TEXT PRIMARY KEY NOT NULL,
+    event TEXT NOT NULL,
+    actor_id TEXT,
+    target_id TEXT,
+    ip_address TEXT,
+    details TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS audit_events_created_at_idx ON audit_events (created_at);
+CREATE INDEX IF NOT EXISTS audit_events_actor_id_idx ON audit_events (actor_id, created_at);
+
+CREATE TRIGGER IF NOT EXISTS audit_events_no_update
+BEFORE UPDATE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS audit_events_no_delete
+BEFORE DELETE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
@@ -0,0 +1,4 @@
+-- Written as RFC 3339 in UTC, so text comparison and ordering follow time.
+ALTER TABLE todos ADD COLUMN due_at TEXT;
+
+CREATE INDEX IF NOT EXISTS todos_user_id_due_at_idx ON todos (user_id, due_at, id);
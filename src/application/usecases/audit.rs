@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use validator::Validate;
+
+use crate::domain::{
+    entities::audit_events::AuditEventEntity, errors::DomainError,
+    repositories::audit::AuditRepository, value_objects::audit_events::ListAuditEventsModel,
+};
+
+pub struct AuditUseCase<A>
+where
+    A: AuditRepository + Send + Sync,
+{
+    audit_repository: Arc<A>,
+}
+
+impl<A> AuditUseCase<A>
+where
+    A: AuditRepository + Send + Sync,
+{
+    pub fn new(audit_repository: Arc<A>) -> Self {
+        Self { audit_repository }
+    }
+
+    pub async fn list(
+        &self,
+        list_model: ListAuditEventsModel,
+    ) -> Result<Vec<AuditEventEntity>, DomainError> {
+        list_model.validate()?;
+
+        self.audit_repository.query(list_model.to_entity()).await
+    }
+}
@@ -1,96 +1,783 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use anyhow::Result;
 use chrono::{Duration, Utc};
+use rand::Rng;
+use serde_json::{Value, json};
+use tracing::warn;
+use uuid::Uuid;
 
 use crate::{
-    domain::repositories::users::UsersRepository,
-    infrastructure::jwt_authentication::{
-        self,
-        authentication_model::LoginModel,
-        jwt_model::{Claims, Passport},
+    application::usecases::totp,
+    config::config_model::{Config, OidcProvider},
+    domain::{
+        entities::{
+            audit_events::{AddAuditEventEntity, AuditEventKind},
+            refresh_tokens::RefreshTokenEntity,
+            sessions::SessionEntity,
+            user_identities::UserIdentityEntity,
+            users::{RegisterUserEntity, Role, UserEntity},
+        },
+        errors::DomainError,
+        repositories::{audit::AuditRepository, users::UsersRepository},
+    },
+    infrastructure::{
+        argon2_hashing,
+        jwt_authentication::{
+            self,
+            authentication_model::{
+                DeviceModel, LoginModel, LoginOutcome, OidcAuthorizationModel, OidcCallbackModel,
+                TotpChallengeModel, TotpLoginModel,
+            },
+            jwt_keys::JwtKeys,
+            jwt_model::{ChallengeClaims, Claims, IdentityClaims, OidcStateClaims, Passport},
+        },
+        login_throttle::LoginThrottle,
+        oidc::{OidcClient, OidcIdentity},
+        token_hashing,
     },
 };
 
-pub struct AuthenticationUseCase<T>
+const TOTP_CHALLENGE_PURPOSE: &str = "totp";
+
+pub struct AuthenticationUseCase<T, A>
 where
     T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
 {
     users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+    jwt_keys: Arc<JwtKeys>,
+    config: Arc<Config>,
+    login_throttle: Arc<LoginThrottle>,
+    oidc_clients: HashMap<String, OidcClient>,
 }
 
-impl<T> AuthenticationUseCase<T>
+impl<T, A> AuthenticationUseCase<T, A>
 where
     T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
 {
-    pub fn new(users_repository: Arc<T>) -> Self {
-        Self { users_repository }
+    pub fn new(
+        users_repository: Arc<T>,
+        audit_repository: Arc<A>,
+        jwt_keys: Arc<JwtKeys>,
+        login_throttle: Arc<LoginThrottle>,
+        config: Arc<Config>,
+    ) -> Self {
+        // Computed now so the first login for an unknown username isn't the slow one.
+        argon2_hashing::dummy_hash();
+
+        Self {
+            oidc_clients: config
+                .oidc
+                .providers
+                .iter()
+                .map(|p| (p.name.clone(), OidcClient::new(p.clone())))
+                .collect(),
+            users_repository,
+            audit_repository,
+            jwt_keys,
+            login_throttle,
+            config,
+        }
     }
 
-    pub async fn login(&self, login_model: LoginModel) -> Result<Passport> {
-        if let Some(user) = self
+    pub async fn login(
+        &self,
+        login_model: LoginModel,
+        device: DeviceModel,
+    ) -> Result<LoginOutcome, DomainError> {
+        let throttle_keys = [
+            format!("username:{}", login_model.username.to_lowercase()),
+            format!("ip:{}", device.ip_address),
+        ];
+        self.check_throttle(&throttle_keys)?;
+
+        let user = self
             .users_repository
             .find_by_username(login_model.username.clone())
+            .await?;
+
+        let password_hash = user
+            .as_ref()
+            .map_or(argon2_hashing::dummy_hash(), |u| u.password_hash.as_str());
+        let password_matches = argon2_hashing::verify(&login_model.password, password_hash)?;
+
+        let user = match user {
+            Some(user) if password_matches => user,
+            user => {
+                self.login_throttle.record_failure(&throttle_keys);
+                self.record_login_failure(
+                    user.map(|u| u.id),
+                    &device.ip_address,
+                    json!({
+                        "method": "password",
+                        "username": login_model.username,
+                        "reason": "invalid_credentials",
+                    }),
+                )
+                .await?;
+
+                return Err(DomainError::Unauthorized(
+                    "Invalid username or password".to_string(),
+                ));
+            }
+        };
+
+        // Only the username is cleared, so one good account can't unlock an IP.
+        self.login_throttle.reset(&throttle_keys[0]);
+
+        if self.config.email_verification.required && !user.verified {
+            self.record_login_failure(
+                Some(user.id),
+                &device.ip_address,
+                json!({ "method": "password", "reason": "email_not_verified" }),
+            )
+            .await?;
+
+            return Err(DomainError::Forbidden(
+                "Email address is not verified".to_string(),
+            ));
+        }
+
+        if argon2_hashing::needs_rehash(&user.password_hash)? {
+            let password_hash = argon2_hashing::hash(&login_model.password)?;
+
+            self.users_repository
+                .update_password(user.id.clone(), password_hash)
+                .await?;
+        }
+
+        if user.totp_enabled {
+            return Ok(LoginOutcome::TotpRequired(
+                self.issue_totp_challenge(&user, None)?,
+            ));
+        }
+
+        Ok(LoginOutcome::Passport(
+            self.start_session(&user, device, "password").await?,
+        ))
+    }
+
+    pub async fn login_totp(
+        &self,
+        totp_login_model: TotpLoginModel,
+        device: DeviceModel,
+    ) -> Result<Passport, DomainError> {
+        let claims = jwt_authentication::verify_toke::<ChallengeClaims>(
+            self.config.jwt.access_secret.clone(),
+            totp_login_model.challenge_token,
+        )
+        .ok()
+        .filter(|c| c.purpose == TOTP_CHALLENGE_PURPOSE)
+        .ok_or_else(|| DomainError::Unauthorized("Invalid challenge token".to_string()))?;
+
+        // Six digit codes are guessable, so they are throttled per account as well.
+        let throttle_keys = [
+            format!("totp:{}", claims.sub),
+            format!("ip:{}", device.ip_address),
+        ];
+        self.check_throttle(&throttle_keys)?;
+
+        let user = self
+            .users_repository
+            .find_by_id(claims.sub.clone())
+            .await?
+            .filter(|u| u.totp_enabled)
+            .ok_or_else(|| DomainError::Unauthorized("Invalid challenge token".to_string()))?;
+
+        if !totp::verify_second_factor(
+            self.users_repository.as_ref(),
+            &user,
+            &totp_login_model.code,
+        )
+        .await?
+        {
+            self.login_throttle.record_failure(&throttle_keys);
+            self.record_login_failure(
+                Some(user.id),
+                &device.ip_address,
+                json!({ "method": "totp", "reason": "invalid_code" }),
+            )
+            .await?;
+
+            return Err(DomainError::Unauthorized("Invalid code".to_string()));
+        }
+
+        self.login_throttle.reset(&throttle_keys[0]);
+
+        if let Some(identity) = claims.link_identity {
+            match self
+                .users_repository
+                .add_identity(UserIdentityEntity {
+                    issuer: identity.issuer,
+                    subject: identity.subject,
+                    user_id: user.id.clone(),
+                    created_at: Utc::now().naive_utc(),
+                })
+                .await
+            {
+                // A second challenge for the same identity may have linked it already.
+                Ok(()) | Err(DomainError::Conflict(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.start_session(&user, device, "totp").await
+    }
+
+    pub async fn start_oidc_login(
+        &self,
+        provider: String,
+    ) -> Result<OidcAuthorizationModel, DomainError> {
+        let oidc_client = self.oidc_client(&provider)?;
+        let now = Utc::now();
+
+        let claims = OidcStateClaims {
+            provider,
+            state: token_hashing::generate(""),
+            nonce: token_hashing::generate(""),
+            code_verifier: token_hashing::generate(""),
+            exp: (now + Duration::seconds(self.config.oidc.state_ttl_secs)).timestamp() as usize,
+            iat: now.timestamp() as usize,
+        };
+
+        let authorization_url = oidc_client
+            .authorization_url(&claims.state, &claims.nonce, &claims.code_verifier)
+            .await?;
+
+        Ok(OidcAuthorizationModel {
+            authorization_url,
+            state_token: jwt_authentication::generate_token(
+                self.config.jwt.access_secret.clone(),
+                &claims,
+            )?,
+        })
+    }
+
+    // An identity that is already linked is trusted on its own. Linking a new one by email
+    // asks for the account's 2FA first, since the provider only vouches for the address.
+    pub async fn login_oidc(
+        &self,
+        provider: String,
+        callback: OidcCallbackModel,
+        state_token: Option<String>,
+        device: DeviceModel,
+    ) -> Result<LoginOutcome, DomainError> {
+        let oidc_client = self.oidc_client(&provider)?;
+
+        // The state must match the cookie set on this browser, so a login can't be
+        // started by one browser and finished by another.
+        let claims = state_token
+            .and_then(|token| {
+                jwt_authentication::verify_toke::<OidcStateClaims>(
+                    self.config.jwt.access_secret.clone(),
+                    token,
+                )
+                .ok()
+            })
+            .filter(|c| {
+                c.provider == provider
+                    && callback.state.as_ref().is_some_and(|state| {
+                        token_hashing::constant_time_eq(state.as_bytes(), c.state.as_bytes())
+                    })
+            })
+            .ok_or_else(|| DomainError::Unauthorized("Invalid OIDC state".to_string()))?;
+
+        if let Some(error) = callback.error {
+            return Err(DomainError::Unauthorized(format!(
+                "The identity provider rejected the login: {}",
+                error
+            )));
+        }
+        let code = callback
+            .code
+            .ok_or_else(|| DomainError::validation("Missing authorization code"))?;
+
+        let identity = match oidc_client
+            .exchange_code(&code, &claims.code_verifier, &claims.nonce)
+            .await
+        {
+            Ok(identity) => identity,
+            Err(e) => {
+                warn!("OIDC login with {} failed: {:?}", provider, e);
+                self.record_login_failure(
+                    None,
+                    &device.ip_address,
+                    json!({ "method": "oidc", "provider": provider, "reason": "invalid_token" }),
+                )
+                .await?;
+
+                return Err(DomainError::Unauthorized("OIDC login failed".to_string()));
+            }
+        };
+
+        let issuer = identity.issuer.clone();
+        let subject = identity.subject.clone();
+
+        let (user, new_identity) = match self
+            .user_for_identity(oidc_client.provider(), identity)
+            .await
+        {
+            Ok(matched) => matched,
+            Err(e @ DomainError::Forbidden(_)) => {
+                self.record_login_failure(
+                    None,
+                    &device.ip_address,
+                    json!({
+                        "method": "oidc",
+                        "provider": provider,
+                        "issuer": issuer,
+                        "subject": subject,
+                        "reason": "not_linked",
+                    }),
+                )
+                .await?;
+
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+
+        if self.config.email_verification.required && !user.verified {
+            self.record_login_failure(
+                Some(user.id),
+                &device.ip_address,
+                json!({ "method": "oidc", "provider": provider, "reason": "email_not_verified" }),
+            )
+            .await?;
+
+            return Err(DomainError::Forbidden(
+                "Email address is not verified".to_string(),
+            ));
+        }
+
+        if let Some(identity) = new_identity {
+            if user.totp_enabled {
+                return Ok(LoginOutcome::TotpRequired(self.issue_totp_challenge(
+                    &user,
+                    Some(IdentityClaims {
+                        issuer: identity.issuer,
+                        subject: identity.subject,
+                    }),
+                )?));
+            }
+
+            self.users_repository.add_identity(identity).await?;
+        }
+
+        Ok(LoginOutcome::Passport(
+            self.start_session(&user, device, "oidc").await?,
+        ))
+    }
+
+    pub async fn refresh_token(
+        &self,
+        refresh_token: String,
+        device: DeviceModel,
+    ) -> Result<Passport, DomainError> {
+        let current = self.current_refresh_token(refresh_token).await?;
+
+        if current.rotated_at.is_some() {
+            return self.reject_reuse(current, device.ip_address).await;
+        }
+
+        // The role is read again so promotions and demotions apply on the next refresh.
+        let user = self
+            .users_repository
+            .find_by_id(current.user_id.clone())
+            .await?
+            .ok_or_else(|| DomainError::Unauthorized("Invalid refresh token".to_string()))?;
+
+        let (passport, next) = self.issue_passport(&user, current.family_id.clone())?;
+
+        if !self
+            .users_repository
+            .rotate_refresh_token(current.jti.clone(), next)
             .await?
         {
-            if user.password == login_model.password {
-                let access_token_claims = Claims {
-                    sub: user.id.clone(),
-                    exp: (Utc::now() + Duration::days(1)).timestamp() as usize,
-                    iat: Utc::now().timestamp() as usize,
-                };
-
-                let refresh_token_claims = Claims {
-                    sub: user.id,
-                    exp: (Utc::now() + Duration::days(7)).timestamp() as usize,
-                    iat: Utc::now().timestamp() as usize,
-                };
-
-                let access_token =
-                    jwt_authentication::generate_token("secret".to_string(), &access_token_claims)?;
-                let refresh_token = jwt_authentication::generate_token(
-                    "refresh_secret".to_string(),
-                    &refresh_token_claims,
-                )?;
-
-                Ok(Passport {
-                    refresh_token,
-                    access_token,
+            // Another request rotated the same token first.
+            return self.reject_reuse(current, device.ip_address).await;
+        }
+
+        self.users_repository
+            .touch_session(
+                current.family_id.clone(),
+                device.ip_address.clone(),
+                device.user_agent,
+            )
+            .await?;
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(user.id),
+                ip_address: Some(device.ip_address),
+                details: Some(json!({ "session_id": current.family_id })),
+                ..AddAuditEventEntity::new(AuditEventKind::TokenRefreshed)
+            })
+            .await?;
+
+        Ok(passport)
+    }
+
+    pub async fn logout(
+        &self,
+        refresh_token: String,
+        ip_address: String,
+    ) -> Result<(), DomainError> {
+        let current = self.current_refresh_token(refresh_token).await?;
+
+        self.users_repository
+            .revoke_refresh_token_family(current.family_id.clone())
+            .await?;
+
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(current.user_id),
+                ip_address: Some(ip_address),
+                details: Some(json!({ "session_id": current.family_id })),
+                ..AddAuditEventEntity::new(AuditEventKind::Logout)
+            })
+            .await
+    }
+
+    pub async fn logout_all(
+        &self,
+        refresh_token: String,
+        ip_address: String,
+    ) -> Result<(), DomainError> {
+        let current = self.current_refresh_token(refresh_token).await?;
+
+        self.users_repository
+            .revoke_all_refresh_tokens(current.user_id.clone())
+            .await?;
+
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(current.user_id),
+                ip_address: Some(ip_address),
+                details: Some(json!({ "all_sessions": true })),
+                ..AddAuditEventEntity::new(AuditEventKind::Logout)
+            })
+            .await
+    }
+
+    async fn current_refresh_token(
+        &self,
+        refresh_token: String,
+    ) -> Result<RefreshTokenEntity, DomainError> {
+        let claims = jwt_authentication::verify_toke::<Claims>(
+            self.config.jwt.refresh_secret.clone(),
+            refresh_token,
+        )
+        .map_err(|_| DomainError::Unauthorized("Invalid refresh token".to_string()))?;
+
+        self.users_repository
+            .find_refresh_token(claims.jti)
+            .await?
+            .filter(|t| t.user_id == claims.sub && t.revoked_at.is_none())
+            .ok_or_else(|| DomainError::Unauthorized("Invalid refresh token".to_string()))
+    }
+
+    async fn reject_reuse(
+        &self,
+        current: RefreshTokenEntity,
+        ip_address: String,
+    ) -> Result<Passport, DomainError> {
+        self.users_repository
+            .revoke_refresh_token_family(current.family_id.clone())
+            .await?;
+
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(current.user_id),
+                ip_address: Some(ip_address),
+                details: Some(json!({ "session_id": current.family_id })),
+                ..AddAuditEventEntity::new(AuditEventKind::TokenReuseDetected)
+            })
+            .await?;
+
+        Err(DomainError::Unauthorized(
+            "Refresh token reuse detected".to_string(),
+        ))
+    }
+
+    fn oidc_client(&self, provider: &str) -> Result<&OidcClient, DomainError> {
+        self.oidc_clients
+            .get(provider)
+            .ok_or_else(|| DomainError::NotFound("Identity provider not found".to_string()))
+    }
+
+    // Also returns the identity to link when the user was found by email or provisioned.
+    async fn user_for_identity(
+        &self,
+        provider: &OidcProvider,
+        identity: OidcIdentity,
+    ) -> Result<(UserEntity, Option<UserIdentityEntity>), DomainError> {
+        if let Some(user) = self
+            .users_repository
+            .find_by_identity(identity.issuer.clone(), identity.subject.clone())
+            .await?
+        {
+            return Ok((user, None));
+        }
+
+        let verified_email = identity.email.clone().filter(|_| identity.email_verified);
+
+        let user = match self
+            .linkable_user(provider, verified_email.as_deref())
+            .await?
+        {
+            Some(user) => user,
+            None if provider.auto_provision => {
+                self.provision_user(&identity, verified_email).await?
+            }
+            None => {
+                return Err(DomainError::Forbidden(
+                    "No account is linked to this identity".to_string(),
+                ));
+            }
+        };
+
+        let new_identity = UserIdentityEntity {
+            issuer: identity.issuer,
+            subject: identity.subject,
+            user_id: user.id.clone(),
+            created_at: Utc::now().naive_utc(),
+        };
+
+        Ok((user, Some(new_identity)))
+    }
+
+    // Both sides must have verified the address, and it must point at a single account.
+    async fn linkable_user(
+        &self,
+        provider: &OidcProvider,
+        verified_email: Option<&str>,
+    ) -> Result<Option<UserEntity>, DomainError> {
+        let Some(email) = verified_email.filter(|_| provider.link_by_email) else {
+            return Ok(None);
+        };
+
+        let mut users = self
+            .users_repository
+            .find_by_email(email.to_string())
+            .await?;
+        users.retain(|u| u.verified);
+
+        Ok(match users.len() {
+            1 => users.pop(),
+            _ => None,
+        })
+    }
+
+    // Provisioned users get a random password; they can set one through a password reset.
+    async fn provision_user(
+        &self,
+        identity: &OidcIdentity,
+        verified_email: Option<String>,
+    ) -> Result<UserEntity, DomainError> {
+        let base_username = username_hint(identity);
+        let password_hash = argon2_hashing::hash(&token_hashing::generate(""))?;
+
+        for attempt in 0..5 {
+            let username = match attempt {
+                0 => base_username.clone(),
+                _ => format!(
+                    "{}-{}",
+                    base_username,
+                    rand::thread_rng().gen_range(1000..10000)
+                ),
+            };
+
+            let result = self
+                .users_repository
+                .register(RegisterUserEntity {
+                    username: username.clone(),
+                    password_hash: password_hash.clone(),
+                    role: Role::User,
+                    email: verified_email.clone(),
+                    verified: verified_email.is_some(),
                 })
-            } else {
-                Err(anyhow::anyhow!("Invalid password"))
+                .await;
+
+            match result {
+                Ok(()) => {
+                    return self
+                        .users_repository
+                        .find_by_username(username)
+                        .await?
+                        .ok_or_else(|| {
+                            DomainError::Internal(anyhow::anyhow!("Provisioned user is missing"))
+                        });
+                }
+                Err(DomainError::Conflict(_)) => continue,
+                Err(e) => return Err(e),
             }
-        } else {
-            Err(anyhow::anyhow!("User not found"))
         }
+
+        Err(DomainError::Conflict(
+            "Could not find a free username".to_string(),
+        ))
+    }
+
+    // Failures are recorded against the account when one was named, so targeted accounts
+    // stand out when filtering by actor.
+    async fn record_login_failure(
+        &self,
+        user_id: Option<String>,
+        ip_address: &str,
+        details: Value,
+    ) -> Result<(), DomainError> {
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: user_id,
+                ip_address: Some(ip_address.to_string()),
+                details: Some(details),
+                ..AddAuditEventEntity::new(AuditEventKind::LoginFailed)
+            })
+            .await
+    }
+
+    fn check_throttle(&self, keys: &[String]) -> Result<(), DomainError> {
+        self.login_throttle
+            .check(keys, "Too many failed login attempts")
+    }
+
+    async fn start_session(
+        &self,
+        user: &UserEntity,
+        device: DeviceModel,
+        method: &str,
+    ) -> Result<Passport, DomainError> {
+        let (passport, refresh_token) = self.issue_passport(user, Uuid::new_v4().to_string())?;
+        let session_id = refresh_token.family_id.clone();
+        let now = Utc::now().naive_utc();
+
+        self.users_repository
+            .add_session(SessionEntity {
+                id: session_id.clone(),
+                user_id: user.id.clone(),
+                user_agent: device.user_agent,
+                ip_address: device.ip_address.clone(),
+                created_at: now,
+                last_used_at: now,
+            })
+            .await?;
+        self.users_repository
+            .add_refresh_token(refresh_token)
+            .await?;
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(user.id.clone()),
+                ip_address: Some(device.ip_address),
+                details: Some(json!({ "method": method, "session_id": session_id })),
+                ..AddAuditEventEntity::new(AuditEventKind::LoginSucceeded)
+            })
+            .await?;
+
+        Ok(passport)
+    }
+
+    fn issue_totp_challenge(
+        &self,
+        user: &UserEntity,
+        link_identity: Option<IdentityClaims>,
+    ) -> Result<TotpChallengeModel, DomainError> {
+        let now = Utc::now();
+
+        let claims = ChallengeClaims {
+            sub: user.id.clone(),
+            purpose: TOTP_CHALLENGE_PURPOSE.to_string(),
+            exp: (now + Duration::seconds(self.config.totp.challenge_token_ttl_secs)).timestamp()
+                as usize,
+            iat: now.timestamp() as usize,
+            link_identity,
+        };
+
+        Ok(TotpChallengeModel {
+            totp_required: true,
+            challenge_token: jwt_authentication::generate_token(
+                self.config.jwt.access_secret.clone(),
+                &claims,
+            )?,
+        })
     }
 
-    pub async fn refresh_token(&self, refresh_token: String) -> Result<Passport> {
-        let claims = jwt_authentication::verify_toke("refresh_secret".to_string(), refresh_token)?;
+    fn issue_passport(
+        &self,
+        user: &UserEntity,
+        family_id: String,
+    ) -> Result<(Passport, RefreshTokenEntity), DomainError> {
+        let now = Utc::now();
+        let refresh_expires_at = now + Duration::seconds(self.config.jwt.refresh_token_ttl_secs);
 
         let access_token_claims = Claims {
-            sub: claims.sub.clone(),
-            exp: (Utc::now() + Duration::days(1)).timestamp() as usize,
-            iat: Utc::now().timestamp() as usize,
+            iss: self.config.jwt.issuer.clone(),
+            aud: self.config.jwt.audience.clone(),
+            sub: user.id.clone(),
+            role: user.role,
+            jti: Uuid::new_v4().to_string(),
+            exp: (now + Duration::seconds(self.config.jwt.access_token_ttl_secs)).timestamp()
+                as usize,
+            iat: now.timestamp() as usize,
+            sid: Some(family_id.clone()),
         };
 
         let refresh_token_claims = Claims {
-            sub: claims.sub,
-            exp: claims.exp,
-            iat: Utc::now().timestamp() as usize,
+            iss: self.config.jwt.issuer.clone(),
+            aud: self.config.jwt.audience.clone(),
+            sub: user.id.clone(),
+            role: user.role,
+            jti: Uuid::new_v4().to_string(),
+            exp: refresh_expires_at.timestamp() as usize,
+            iat: now.timestamp() as usize,
+            sid: None,
         };
 
-        let access_token =
-            jwt_authentication::generate_token("secret".to_string(), &access_token_claims)?;
-
+        let access_token = self.jwt_keys.generate_token(&access_token_claims)?;
         let refresh_token = jwt_authentication::generate_token(
-            "refresh_secret".to_string(),
+            self.config.jwt.refresh_secret.clone(),
             &refresh_token_claims,
         )?;
 
-        Ok(Passport {
-            refresh_token,
-            access_token,
-        })
+        let refresh_token_entity = RefreshTokenEntity {
+            jti: refresh_token_claims.jti,
+            family_id,
+            user_id: user.id.clone(),
+            expires_at: refresh_expires_at.naive_utc(),
+            created_at: now.naive_utc(),
+            rotated_at: None,
+            revoked_at: None,
+        };
+
+        Ok((
+            Passport {
+                refresh_token,
+                access_token,
+            },
+            refresh_token_entity,
+        ))
+    }
+}
+
+// Derives a username from the identity, limited to the characters and length that
+// registration accepts, leaving room for a "-1234" suffix on collisions.
+fn username_hint(identity: &OidcIdentity) -> String {
+    let hint = identity
+        .preferred_username
+        .as_deref()
+        .or_else(|| identity.email.as_deref().and_then(|e| e.split('@').next()))
+        .unwrap_or_default();
+
+    let username = hint
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        .take(27)
+        .collect::<String>();
+
+    if username.len() < 3 {
+        "user".to_string()
+    } else {
+        username
     }
 }
@@ -1,3 +1,9 @@
+pub mod audit;
 pub mod authentication;
+pub mod passwords;
+pub mod personal_access_tokens;
+pub mod profiles;
+pub mod sessions;
 pub mod todos;
+pub mod totp;
 pub mod users;
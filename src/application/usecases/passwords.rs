@@ -0,0 +1,255 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    config::config_model::Config,
+    domain::{
+        entities::{
+            audit_events::{AddAuditEventEntity, AuditEventKind},
+            password_reset_tokens::PasswordResetTokenEntity,
+        },
+        errors::{DomainError, FieldErrors},
+        mailer::{MailMessage, Mailer},
+        repositories::{audit::AuditRepository, users::UsersRepository},
+        value_objects::users::{
+            ChangePasswordModel, ForgotPasswordModel, PasswordPolicyModel, ResetPasswordModel,
+        },
+    },
+    infrastructure::{
+        argon2_hashing,
+        jwt_authentication::{self, jwt_model::Claims},
+        login_throttle::LoginThrottle,
+        token_hashing,
+    },
+};
+
+pub struct PasswordsUseCase<T, M, A>
+where
+    T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+    mailer: Arc<M>,
+    password_policy: Arc<PasswordPolicyModel>,
+    login_throttle: Arc<LoginThrottle>,
+    config: Arc<Config>,
+}
+
+impl<T, M, A> PasswordsUseCase<T, M, A>
+where
+    T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    pub fn new(
+        users_repository: Arc<T>,
+        audit_repository: Arc<A>,
+        mailer: Arc<M>,
+        password_policy: Arc<PasswordPolicyModel>,
+        login_throttle: Arc<LoginThrottle>,
+        config: Arc<Config>,
+    ) -> Self {
+        Self {
+            users_repository,
+            audit_repository,
+            mailer,
+            password_policy,
+            login_throttle,
+            config,
+        }
+    }
+
+    // `refresh_token` identifies the caller's own session, which survives the change.
+    pub async fn change_password(
+        &self,
+        user_id: String,
+        change_password_model: ChangePasswordModel,
+        refresh_token: Option<String>,
+        ip_address: String,
+    ) -> Result<(), DomainError> {
+        let user = self
+            .users_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("User not found".to_string()))?;
+
+        // A stolen session could otherwise guess the current password without limit.
+        let throttle_keys = [format!("password:{}", user.id), format!("ip:{ip_address}")];
+        self.login_throttle
+            .check(&throttle_keys, "Too many failed attempts")?;
+
+        if !argon2_hashing::verify(&change_password_model.current_password, &user.password_hash)? {
+            self.login_throttle.record_failure(&throttle_keys);
+            return Err(DomainError::invalid_fields(FieldErrors::from([(
+                "current_password".to_string(),
+                vec!["Current password is incorrect".to_string()],
+            )])));
+        }
+        self.login_throttle.reset(&throttle_keys[0]);
+
+        self.password_policy.validate_with(
+            &change_password_model,
+            "new_password",
+            &change_password_model.new_password,
+        )?;
+
+        let password_hash = argon2_hashing::hash(&change_password_model.new_password)?;
+        self.users_repository
+            .update_password(user.id.clone(), password_hash)
+            .await?;
+
+        match self.session_family(&user.id, refresh_token).await? {
+            Some(family_id) => {
+                self.users_repository
+                    .revoke_other_refresh_tokens(user.id.clone(), family_id)
+                    .await?
+            }
+            None => {
+                self.users_repository
+                    .revoke_all_refresh_tokens(user.id.clone())
+                    .await?
+            }
+        }
+
+        if change_password_model.revoke_personal_access_tokens {
+            self.users_repository
+                .revoke_all_personal_access_tokens(user.id.clone())
+                .await?;
+        }
+
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(user.id),
+                ip_address: Some(ip_address),
+                ..AddAuditEventEntity::new(AuditEventKind::PasswordChanged)
+            })
+            .await
+    }
+
+    // Always succeeds so the response doesn't reveal which usernames exist. Mail only
+    // goes to a verified address; accounts without one can't reset by mail.
+    pub async fn forgot_password(
+        &self,
+        forgot_password_model: ForgotPasswordModel,
+        ip_address: &str,
+    ) -> Result<(), DomainError> {
+        // Every request counts, not just failures, so nobody can flood an inbox with mail.
+        let throttle_keys = [
+            format!(
+                "forgot-password:username:{}",
+                forgot_password_model.username.to_lowercase()
+            ),
+            format!("forgot-password:ip:{ip_address}"),
+        ];
+        self.login_throttle
+            .check(&throttle_keys, "Too many password reset requests")?;
+        self.login_throttle.record_failure(&throttle_keys);
+
+        let Some((user, email)) = self
+            .users_repository
+            .find_by_username(forgot_password_model.username)
+            .await?
+            .filter(|u| u.verified)
+            .and_then(|u| u.email.clone().map(|email| (u, email)))
+        else {
+            return Ok(());
+        };
+
+        let token = token_hashing::generate("");
+        let now = Utc::now();
+
+        self.users_repository
+            .add_password_reset_token(PasswordResetTokenEntity {
+                id: Uuid::new_v4().to_string(),
+                user_id: user.id,
+                token_hash: token_hashing::hash(&token),
+                expires_at: (now + Duration::seconds(self.config.password_reset.token_ttl_secs))
+                    .naive_utc(),
+                created_at: now.naive_utc(),
+                used_at: None,
+            })
+            .await?;
+
+        let link = format!("{}?token={}", self.config.password_reset.url, token);
+
+        self.mailer
+            .send(MailMessage {
+                to: email,
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "Someone asked to reset the password for your account.\n\n\
+                     Open this link to choose a new password:\n{}\n\n\
+                     The link works once and expires in {} minutes. \
+                     If you didn't ask for this, you can ignore this message.",
+                    link,
+                    self.config.password_reset.token_ttl_secs / 60
+                ),
+            })
+            .await
+    }
+
+    pub async fn reset_password(
+        &self,
+        reset_password_model: ResetPasswordModel,
+        ip_address: String,
+    ) -> Result<(), DomainError> {
+        self.password_policy.validate_with(
+            &reset_password_model,
+            "new_password",
+            &reset_password_model.new_password,
+        )?;
+
+        let user_id = self
+            .users_repository
+            .consume_password_reset_token(token_hashing::hash(&reset_password_model.token))
+            .await?
+            .ok_or_else(|| DomainError::validation("Invalid or expired reset token"))?;
+
+        let password_hash = argon2_hashing::hash(&reset_password_model.new_password)?;
+        self.users_repository
+            .update_password(user_id.clone(), password_hash)
+            .await?;
+
+        self.users_repository
+            .revoke_all_refresh_tokens(user_id.clone())
+            .await?;
+        // Whoever knew the old password may have minted tokens with it.
+        self.users_repository
+            .revoke_all_personal_access_tokens(user_id.clone())
+            .await?;
+
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(user_id),
+                ip_address: Some(ip_address),
+                details: Some(json!({ "method": "email" })),
+                ..AddAuditEventEntity::new(AuditEventKind::PasswordReset)
+            })
+            .await
+    }
+
+    async fn session_family(
+        &self,
+        user_id: &str,
+        refresh_token: Option<String>,
+    ) -> Result<Option<String>, DomainError> {
+        let Some(claims) = refresh_token.and_then(|token| {
+            jwt_authentication::verify_toke::<Claims>(self.config.jwt.refresh_secret.clone(), token)
+                .ok()
+        }) else {
+            return Ok(None);
+        };
+
+        Ok(self
+            .users_repository
+            .find_refresh_token(claims.jti)
+            .await?
+            .filter(|t| t.user_id == user_id && t.revoked_at.is_none())
+            .map(|t| t.family_id))
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use validator::Validate;
+
+use crate::{
+    domain::{
+        entities::personal_access_tokens::PersonalAccessTokenEntity,
+        errors::DomainError,
+        repositories::users::UsersRepository,
+        value_objects::personal_access_tokens::{
+            CreatePersonalAccessTokenModel, CreatedPersonalAccessTokenModel,
+        },
+    },
+    infrastructure::token_hashing,
+};
+
+pub const TOKEN_PREFIX: &str = "pat_";
+
+pub struct PersonalAccessTokensUseCase<T>
+where
+    T: UsersRepository + Send + Sync,
+{
+    users_repository: Arc<T>,
+}
+
+impl<T> PersonalAccessTokensUseCase<T>
+where
+    T: UsersRepository + Send + Sync,
+{
+    pub fn new(users_repository: Arc<T>) -> Self {
+        Self { users_repository }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: String,
+        create_model: CreatePersonalAccessTokenModel,
+    ) -> Result<CreatedPersonalAccessTokenModel, DomainError> {
+        create_model.validate()?;
+
+        let token = token_hashing::generate(TOKEN_PREFIX);
+        let details =
+            create_model.to_entity(user_id, token_hashing::hash(&token), Utc::now().naive_utc());
+
+        self.users_repository
+            .add_personal_access_token(details.clone())
+            .await?;
+
+        Ok(CreatedPersonalAccessTokenModel { token, details })
+    }
+
+    pub async fn list(
+        &self,
+        user_id: String,
+    ) -> Result<Vec<PersonalAccessTokenEntity>, DomainError> {
+        self.users_repository
+            .list_personal_access_tokens(user_id)
+            .await
+    }
+
+    pub async fn revoke(&self, user_id: String, id: String) -> Result<(), DomainError> {
+        self.users_repository
+            .revoke_personal_access_token(user_id, id)
+            .await
+    }
+
+    pub async fn authenticate(
+        &self,
+        token: String,
+    ) -> Result<PersonalAccessTokenEntity, DomainError> {
+        let now = Utc::now().naive_utc();
+
+        let personal_access_token = self
+            .users_repository
+            .find_personal_access_token(token_hashing::hash(&token))
+            .await?
+            .filter(|t| t.revoked_at.is_none() && t.expires_at.is_none_or(|e| e > now))
+            .ok_or_else(|| DomainError::Unauthorized("Invalid access token".to_string()))?;
+
+        self.users_repository
+            .touch_personal_access_token(personal_access_token.id.clone())
+            .await?;
+
+        Ok(personal_access_token)
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use validator::Validate;
+
+use crate::domain::{
+    entities::{
+        audit_events::{AddAuditEventEntity, AuditEventKind},
+        users::UserEntity,
+    },
+    errors::DomainError,
+    repositories::{audit::AuditRepository, users::UsersRepository},
+    value_objects::users::UpdateProfileModel,
+};
+
+pub struct ProfilesUseCase<T, A>
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+}
+
+impl<T, A> ProfilesUseCase<T, A>
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    pub fn new(users_repository: Arc<T>, audit_repository: Arc<A>) -> Self {
+        Self {
+            users_repository,
+            audit_repository,
+        }
+    }
+
+    pub async fn get(&self, user_id: String) -> Result<UserEntity, DomainError> {
+        self.users_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("User not found".to_string()))
+    }
+
+    pub async fn update(
+        &self,
+        user_id: String,
+        profile_model: UpdateProfileModel,
+    ) -> Result<UserEntity, DomainError> {
+        profile_model.validate()?;
+
+        if profile_model.is_empty() {
+            return Err(DomainError::validation("Nothing to update"));
+        }
+
+        if let Some(username) = &profile_model.username
+            && self
+                .users_repository
+                .find_by_username(username.clone())
+                .await?
+                .is_some_and(|u| u.id != user_id)
+        {
+            return Err(DomainError::Conflict("Username already exists".to_string()));
+        }
+
+        self.users_repository
+            .update(user_id, profile_model.to_entity())
+            .await
+    }
+
+    // Todos and everything else the user owns go with the row in a single delete.
+    pub async fn delete(&self, user_id: String, ip_address: String) -> Result<(), DomainError> {
+        self.users_repository.delete(user_id.clone()).await?;
+
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(user_id),
+                ip_address: Some(ip_address),
+                ..AddAuditEventEntity::new(AuditEventKind::AccountDeleted)
+            })
+            .await
+    }
+}
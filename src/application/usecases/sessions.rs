@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use serde_json::json;
+
+use crate::domain::{
+    entities::{
+        audit_events::{AddAuditEventEntity, AuditEventKind},
+        sessions::SessionEntity,
+    },
+    errors::DomainError,
+    repositories::{audit::AuditRepository, users::UsersRepository},
+};
+
+pub struct SessionsUseCase<T, A>
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+}
+
+impl<T, A> SessionsUseCase<T, A>
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    pub fn new(users_repository: Arc<T>, audit_repository: Arc<A>) -> Self {
+        Self {
+            users_repository,
+            audit_repository,
+        }
+    }
+
+    pub async fn list(&self, user_id: String) -> Result<Vec<SessionEntity>, DomainError> {
+        self.users_repository.list_sessions(user_id).await
+    }
+
+    // Revoking the refresh token family also rejects the access tokens carrying its `sid`.
+    pub async fn revoke(
+        &self,
+        user_id: String,
+        id: String,
+        ip_address: String,
+    ) -> Result<(), DomainError> {
+        let sessions = self.users_repository.list_sessions(user_id.clone()).await?;
+
+        if !sessions.iter().any(|s| s.id == id) {
+            return Err(DomainError::NotFound("Session not found".to_string()));
+        }
+
+        self.users_repository
+            .revoke_refresh_token_family(id.clone())
+            .await?;
+
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(user_id),
+                ip_address: Some(ip_address),
+                details: Some(json!({ "session_id": id })),
+                ..AddAuditEventEntity::new(AuditEventKind::SessionRevoked)
+            })
+            .await
+    }
+}
@@ -1,53 +1,187 @@
 use std::sync::Arc;
 
-use anyhow::Result;
+use chrono::{DateTime, Days, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use validator::Validate;
 
 use crate::domain::{
-    entities::todos::TodoEntity, repositories::todos::TodosRepository,
-    value_objects::todos::AddTodoModel,
+    entities::todos::{TodoCursor, TodoEntity},
+    errors::DomainError,
+    repositories::{todos::TodosRepository, users::UsersRepository},
+    value_objects::todos::{
+        AddTodoModel, ListTodosModel, TodoPageModel, TodoViewModel, UpdateTodoModel, encode_cursor,
+    },
 };
 
-pub struct TodosUseCase<T>
+const UPCOMING_DAYS: u64 = 7;
+
+pub struct TodosUseCase<T, U>
 where
     T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
 {
     todo_repository: Arc<T>,
+    users_repository: Arc<U>,
 }
 
-impl<T> TodosUseCase<T>
+impl<T, U> TodosUseCase<T, U>
 where
     T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
 {
-    pub fn new(todo_repository: Arc<T>) -> Self {
-        Self { todo_repository }
+    pub fn new(todo_repository: Arc<T>, users_repository: Arc<U>) -> Self {
+        Self {
+            todo_repository,
+            users_repository,
+        }
     }
 
-    pub async fn list(&self) -> Result<Vec<TodoEntity>> {
-        let result = self.todo_repository.list().await?;
+    pub async fn list(
+        &self,
+        user_id: String,
+        list_model: ListTodosModel,
+    ) -> Result<TodoPageModel, DomainError> {
+        list_model.validate()?;
 
-        Ok(result)
+        let mut query = list_model.to_entity()?;
+        let sort = query.sort;
+        let limit = query.limit as usize;
+
+        // Fetch one extra row to know whether another page follows.
+        query.limit += 1;
+        let mut data = self.todo_repository.query(user_id, query).await?;
+
+        let next_cursor = if data.len() > limit {
+            data.truncate(limit);
+            data.last().map(|todo| {
+                encode_cursor(&TodoCursor {
+                    key: sort.key_of(todo),
+                    id: todo.id.clone(),
+                })
+            })
+        } else {
+            None
+        };
+
+        Ok(TodoPageModel { data, next_cursor })
     }
 
-    pub async fn get(&self, id: String) -> Result<TodoEntity> {
-        let result = self.todo_repository.get(id).await?;
+    // Todos due during the user's current local day.
+    pub async fn today(
+        &self,
+        user_id: String,
+        view_model: TodoViewModel,
+    ) -> Result<TodoPageModel, DomainError> {
+        view_model.validate()?;
+
+        let (timezone, today) = self.local_today(&user_id).await?;
+        let list_model = view_model.to_list_model(
+            start_of_day(timezone, today),
+            start_of_day(timezone, next_day(today, 1)?),
+        );
+
+        self.list(user_id, list_model).await
+    }
+
+    // Todos due from tomorrow through the following week, in the user's timezone.
+    pub async fn upcoming(
+        &self,
+        user_id: String,
+        view_model: TodoViewModel,
+    ) -> Result<TodoPageModel, DomainError> {
+        view_model.validate()?;
+
+        let (timezone, today) = self.local_today(&user_id).await?;
+        let list_model = view_model.to_list_model(
+            start_of_day(timezone, next_day(today, 1)?),
+            start_of_day(timezone, next_day(today, 1 + UPCOMING_DAYS)?),
+        );
+
+        self.list(user_id, list_model).await
+    }
+
+    pub async fn get(&self, user_id: String, id: String) -> Result<TodoEntity, DomainError> {
+        let result = self.todo_repository.get(user_id, id).await?;
 
         Ok(result)
     }
 
-    pub async fn add(&self, user_id: String, todo_model: AddTodoModel) -> Result<TodoEntity> {
+    pub async fn add(
+        &self,
+        user_id: String,
+        todo_model: AddTodoModel,
+    ) -> Result<TodoEntity, DomainError> {
+        todo_model.validate()?;
+
         let todo_entity = todo_model.to_entity();
         let result = self.todo_repository.add(user_id, todo_entity).await?;
 
         Ok(result)
     }
 
-    pub async fn to_completed(&self, id: String) -> Result<TodoEntity> {
-        let result = self.todo_repository.to_completed(id).await?;
+    pub async fn update(
+        &self,
+        user_id: String,
+        id: String,
+        todo_model: UpdateTodoModel,
+    ) -> Result<TodoEntity, DomainError> {
+        todo_model.validate()?;
+
+        if todo_model.is_empty() {
+            return Err(DomainError::validation("Nothing to update"));
+        }
+
+        let result = self
+            .todo_repository
+            .update(user_id, id, todo_model.to_entity())
+            .await?;
 
         Ok(result)
     }
 
-    pub async fn delete(&self, id: String) -> Result<()> {
-        self.todo_repository.delete(id).await
+    pub async fn to_completed(
+        &self,
+        user_id: String,
+        id: String,
+    ) -> Result<TodoEntity, DomainError> {
+        let result = self.todo_repository.to_completed(user_id, id).await?;
+
+        Ok(result)
+    }
+
+    pub async fn delete(&self, user_id: String, id: String) -> Result<(), DomainError> {
+        self.todo_repository.delete(user_id, id).await
+    }
+
+    async fn local_today(&self, user_id: &str) -> Result<(Tz, NaiveDate), DomainError> {
+        let user = self
+            .users_repository
+            .find_by_id(user_id.to_string())
+            .await?
+            .ok_or_else(|| DomainError::NotFound("User not found".to_string()))?;
+
+        // Profiles only accept valid names, so this only falls back for legacy rows.
+        let timezone = user.timezone.parse::<Tz>().unwrap_or(Tz::UTC);
+
+        Ok((timezone, Utc::now().with_timezone(&timezone).date_naive()))
     }
 }
+
+fn next_day(date: NaiveDate, days: u64) -> Result<NaiveDate, DomainError> {
+    date.checked_add_days(Days::new(days))
+        .ok_or_else(|| DomainError::validation("Date out of range"))
+}
+
+// Where a DST change skips midnight, the day starts at the first local time that exists.
+fn start_of_day(timezone: Tz, date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+
+    (0..=2)
+        .find_map(|hours| {
+            timezone
+                .from_local_datetime(&(midnight + Duration::hours(hours)))
+                .earliest()
+        })
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
@@ -0,0 +1,225 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    config::config_model::Config,
+    domain::{
+        entities::{recovery_codes::RecoveryCodeEntity, users::UserEntity},
+        errors::DomainError,
+        repositories::users::UsersRepository,
+        value_objects::totp::{
+            RecoveryCodesModel, TotpCodeModel, TotpEnrollmentModel, TotpStepUpModel,
+        },
+    },
+    infrastructure::{argon2_hashing, login_throttle::LoginThrottle, token_hashing, totp},
+};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+pub struct TotpUseCase<T>
+where
+    T: UsersRepository + Send + Sync,
+{
+    users_repository: Arc<T>,
+    login_throttle: Arc<LoginThrottle>,
+    config: Arc<Config>,
+}
+
+impl<T> TotpUseCase<T>
+where
+    T: UsersRepository + Send + Sync,
+{
+    pub fn new(
+        users_repository: Arc<T>,
+        login_throttle: Arc<LoginThrottle>,
+        config: Arc<Config>,
+    ) -> Self {
+        Self {
+            users_repository,
+            login_throttle,
+            config,
+        }
+    }
+
+    pub async fn enroll(
+        &self,
+        user_id: String,
+        step_up_model: TotpStepUpModel,
+        ip_address: &str,
+    ) -> Result<TotpEnrollmentModel, DomainError> {
+        let user = self.find_user(user_id).await?;
+        self.verify_step_up(&user, &step_up_model, ip_address)
+            .await?;
+
+        if user.totp_enabled {
+            return Err(DomainError::Conflict(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        let secret = totp::generate_secret();
+
+        self.users_repository
+            .update_totp(user.id, Some(secret.clone()), false)
+            .await?;
+
+        Ok(TotpEnrollmentModel {
+            otpauth_uri: totp::otpauth_uri(&self.config.totp.issuer, &user.username, &secret),
+            secret,
+        })
+    }
+
+    pub async fn confirm(
+        &self,
+        user_id: String,
+        code_model: TotpCodeModel,
+        ip_address: &str,
+    ) -> Result<RecoveryCodesModel, DomainError> {
+        let user = self.find_user(user_id).await?;
+        let throttle_keys = throttle_keys(&user, ip_address);
+        self.check_throttle(&throttle_keys)?;
+
+        if user.totp_enabled {
+            return Err(DomainError::Conflict(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+        if user.totp_secret.is_none() {
+            return Err(DomainError::validation(
+                "Two-factor enrollment has not been started",
+            ));
+        }
+
+        // No recovery codes exist yet, so only a code from the app can pass here.
+        if !verify_second_factor(self.users_repository.as_ref(), &user, &code_model.code).await? {
+            self.login_throttle.record_failure(&throttle_keys);
+            return Err(DomainError::validation("Invalid code"));
+        }
+        self.login_throttle.reset(&throttle_keys[0]);
+
+        self.users_repository
+            .update_totp(user.id.clone(), user.totp_secret, true)
+            .await?;
+
+        let now = Utc::now().naive_utc();
+        let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| totp::generate_recovery_code())
+            .collect();
+        let entities = recovery_codes
+            .iter()
+            .map(|code| RecoveryCodeEntity {
+                id: Uuid::new_v4().to_string(),
+                user_id: user.id.clone(),
+                code_hash: token_hashing::hash(code),
+                created_at: now,
+                used_at: None,
+            })
+            .collect();
+
+        self.users_repository
+            .replace_recovery_codes(user.id, entities)
+            .await?;
+
+        Ok(RecoveryCodesModel { recovery_codes })
+    }
+
+    pub async fn disable(
+        &self,
+        user_id: String,
+        step_up_model: TotpStepUpModel,
+        ip_address: &str,
+    ) -> Result<(), DomainError> {
+        let user = self.find_user(user_id).await?;
+
+        if !user.totp_enabled {
+            return Err(DomainError::validation(
+                "Two-factor authentication is not enabled",
+            ));
+        }
+
+        self.verify_step_up(&user, &step_up_model, ip_address)
+            .await?;
+
+        self.users_repository
+            .update_totp(user.id.clone(), None, false)
+            .await?;
+        self.users_repository
+            .replace_recovery_codes(user.id, Vec::new())
+            .await
+    }
+
+    // A stolen access token alone must not be enough to change how the account is protected.
+    async fn verify_step_up(
+        &self,
+        user: &UserEntity,
+        step_up_model: &TotpStepUpModel,
+        ip_address: &str,
+    ) -> Result<(), DomainError> {
+        let throttle_keys = throttle_keys(user, ip_address);
+        self.check_throttle(&throttle_keys)?;
+
+        let verified = match (&step_up_model.password, &step_up_model.code) {
+            (Some(password), _) => argon2_hashing::verify(password, &user.password_hash)?,
+            (None, Some(code)) if user.totp_enabled => {
+                verify_second_factor(self.users_repository.as_ref(), user, code).await?
+            }
+            _ => {
+                return Err(DomainError::validation(if user.totp_enabled {
+                    "Current password or a code is required"
+                } else {
+                    "Current password is required"
+                }));
+            }
+        };
+
+        if !verified {
+            self.login_throttle.record_failure(&throttle_keys);
+            return Err(DomainError::validation("Invalid password or code"));
+        }
+        self.login_throttle.reset(&throttle_keys[0]);
+
+        Ok(())
+    }
+
+    fn check_throttle(&self, keys: &[String]) -> Result<(), DomainError> {
+        self.login_throttle.check(keys, "Too many failed attempts")
+    }
+
+    async fn find_user(&self, user_id: String) -> Result<UserEntity, DomainError> {
+        self.users_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("User not found".to_string()))
+    }
+}
+
+// Shares the account key with `login/totp`, so guesses here and there add up.
+fn throttle_keys(user: &UserEntity, ip_address: &str) -> [String; 2] {
+    [format!("totp:{}", user.id), format!("ip:{ip_address}")]
+}
+
+// Accepts either a current code from the authenticator app or an unused recovery code.
+pub async fn verify_second_factor<T>(
+    users_repository: &T,
+    user: &UserEntity,
+    code: &str,
+) -> Result<bool, DomainError>
+where
+    T: UsersRepository + Send + Sync,
+{
+    let code = code.trim();
+
+    if let Some(secret) = &user.totp_secret
+        && let Some(step) = totp::verify(secret, code, Utc::now().timestamp())?
+    {
+        return users_repository
+            .record_totp_step(user.id.clone(), step)
+            .await;
+    }
+
+    users_repository
+        .use_recovery_code(user.id.clone(), token_hashing::hash(&code.to_lowercase()))
+        .await
+}
@@ -1,36 +1,225 @@
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::anyhow;
+use chrono::{Duration, Utc};
+use serde_json::json;
 
-use crate::domain::{
-    entities::users::UserEntity, repositories::users::UsersRepository,
-    value_objects::users::RegisterUserModel,
+use crate::{
+    config::config_model::Config,
+    domain::{
+        entities::{
+            audit_events::{AddAuditEventEntity, AuditEventKind},
+            users::{Role, UserEntity},
+        },
+        errors::{DomainError, FieldErrors},
+        mailer::{MailMessage, Mailer},
+        repositories::{audit::AuditRepository, users::UsersRepository},
+        value_objects::users::{
+            PasswordPolicyModel, RegisterUserModel, UpdateRoleModel, VerifyEmailModel,
+        },
+    },
+    infrastructure::{
+        argon2_hashing,
+        jwt_authentication::{self, jwt_model::ChallengeClaims},
+    },
 };
 
-pub struct UsersUseCase<T>
+const EMAIL_VERIFICATION_PURPOSE: &str = "email_verification";
+
+pub struct UsersUseCase<T, M, A>
 where
     T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
 {
     user_repository: Arc<T>,
+    audit_repository: Arc<A>,
+    mailer: Arc<M>,
+    password_policy: Arc<PasswordPolicyModel>,
+    config: Arc<Config>,
 }
 
-impl<T> UsersUseCase<T>
+impl<T, M, A> UsersUseCase<T, M, A>
 where
     T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
 {
-    pub fn new(user_repository: Arc<T>) -> Self {
-        Self { user_repository }
+    pub fn new(
+        user_repository: Arc<T>,
+        audit_repository: Arc<A>,
+        mailer: Arc<M>,
+        password_policy: Arc<PasswordPolicyModel>,
+        config: Arc<Config>,
+    ) -> Self {
+        Self {
+            user_repository,
+            audit_repository,
+            mailer,
+            password_policy,
+            config,
+        }
     }
 
-    pub async fn register(&self, user_model: RegisterUserModel) -> Result<()> {
-        let user_entity = user_model.to_entity();
+    pub async fn register(
+        &self,
+        user_model: RegisterUserModel,
+        ip_address: String,
+    ) -> Result<(), DomainError> {
+        if self.config.email_verification.required && user_model.email.is_none() {
+            return Err(DomainError::invalid_fields(FieldErrors::from([(
+                "email".to_string(),
+                vec!["Email is required".to_string()],
+            )])));
+        }
 
-        self.user_repository.register(user_entity).await?;
+        let user = self
+            .create(user_model, Role::User, false, Some(ip_address))
+            .await?;
+
+        match user.email.clone() {
+            Some(email) => self.send_verification_email(&user, email).await,
+            None => Ok(()),
+        }
+    }
+
+    pub async fn verify_email(
+        &self,
+        verify_email_model: VerifyEmailModel,
+    ) -> Result<(), DomainError> {
+        let claims = jwt_authentication::verify_toke::<ChallengeClaims>(
+            self.config.jwt.access_secret.clone(),
+            verify_email_model.token,
+        )
+        .ok()
+        .filter(|c| c.purpose == EMAIL_VERIFICATION_PURPOSE)
+        .ok_or_else(|| DomainError::validation("Invalid or expired verification token"))?;
+
+        self.user_repository.mark_verified(claims.sub).await
+    }
+
+    pub async fn bootstrap_admin(&self, user_model: RegisterUserModel) -> Result<(), DomainError> {
+        let users = self.user_repository.list().await?;
+
+        if users.iter().any(|u| u.role == Role::Admin) {
+            return Err(DomainError::Conflict("An admin already exists".to_string()));
+        }
+
+        // Created by the operator, so there is nobody to send a link to.
+        self.create(user_model, Role::Admin, true, None).await?;
 
         Ok(())
     }
 
-    pub async fn list(&self) -> Result<Vec<UserEntity>> {
+    pub async fn update_role(
+        &self,
+        caller_id: String,
+        id: String,
+        update_role_model: UpdateRoleModel,
+        ip_address: String,
+    ) -> Result<(), DomainError> {
+        if caller_id == id {
+            return Err(DomainError::Forbidden(
+                "You cannot change your own role".to_string(),
+            ));
+        }
+
+        // Sessions stay signed in: the role is read again on every request.
+        self.user_repository
+            .update_role(id.clone(), update_role_model.role)
+            .await?;
+
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(caller_id),
+                target_id: Some(id),
+                ip_address: Some(ip_address),
+                details: Some(json!({ "role": update_role_model.role })),
+                ..AddAuditEventEntity::new(AuditEventKind::RoleChanged)
+            })
+            .await
+    }
+
+    async fn create(
+        &self,
+        user_model: RegisterUserModel,
+        role: Role,
+        verified: bool,
+        ip_address: Option<String>,
+    ) -> Result<UserEntity, DomainError> {
+        self.password_policy
+            .validate_with(&user_model, "password", &user_model.password)?;
+
+        if self
+            .user_repository
+            .find_by_username(user_model.username.clone())
+            .await?
+            .is_some()
+        {
+            return Err(DomainError::Conflict("Username already exists".to_string()));
+        }
+
+        let username = user_model.username.clone();
+        let password_hash = argon2_hashing::hash(&user_model.password)?;
+        let user_entity = user_model.to_entity(password_hash, role, verified);
+
+        self.user_repository.register(user_entity).await?;
+
+        let user = self
+            .user_repository
+            .find_by_username(username)
+            .await?
+            .ok_or_else(|| anyhow!("Registered user not found"))?;
+
+        self.audit_repository
+            .add(AddAuditEventEntity {
+                actor_id: Some(user.id.clone()),
+                ip_address,
+                details: Some(json!({ "username": user.username, "role": role })),
+                ..AddAuditEventEntity::new(AuditEventKind::UserRegistered)
+            })
+            .await?;
+
+        Ok(user)
+    }
+
+    async fn send_verification_email(
+        &self,
+        user: &UserEntity,
+        email: String,
+    ) -> Result<(), DomainError> {
+        let now = Utc::now();
+        let ttl_secs = self.config.email_verification.token_ttl_secs;
+
+        let claims = ChallengeClaims {
+            sub: user.id.clone(),
+            purpose: EMAIL_VERIFICATION_PURPOSE.to_string(),
+            exp: (now + Duration::seconds(ttl_secs)).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            link_identity: None,
+        };
+        let token =
+            jwt_authentication::generate_token(self.config.jwt.access_secret.clone(), &claims)?;
+
+        let link = format!("{}?token={}", self.config.email_verification.url, token);
+
+        self.mailer
+            .send(MailMessage {
+                to: email,
+                subject: "Verify your email address".to_string(),
+                body: format!(
+                    "Welcome, {}!\n\n\
+                     Open this link to verify your email address:\n{}\n\n\
+                     The link expires in {} hours.",
+                    user.username,
+                    link,
+                    ttl_secs / 3600
+                ),
+            })
+            .await
+    }
+
+    pub async fn list(&self) -> Result<Vec<UserEntity>, DomainError> {
         let users = self.user_repository.list().await?;
 
         Ok(users)
@@ -0,0 +1,340 @@
+use std::{collections::HashSet, env, fs, path::Path, str::FromStr};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use crate::{
+    config::config_model::{Config, DatabaseBackend, JwtAlgorithm},
+    domain::value_objects::users::PasswordPolicyModel,
+};
+
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+pub fn load() -> Result<Config> {
+    let mut config = match env::var("CONFIG_PATH") {
+        Ok(path) => read_file(&path)?,
+        Err(_) if Path::new(DEFAULT_CONFIG_PATH).exists() => read_file(DEFAULT_CONFIG_PATH)?,
+        Err(_) => Config::default(),
+    };
+
+    apply_env_overrides(&mut config)?;
+    validate(&config)?;
+
+    Ok(config)
+}
+
+pub fn load_password_policy(config: &Config) -> Result<PasswordPolicyModel> {
+    let policy = &config.password_policy;
+
+    let denied_passwords = match &policy.deny_list_path {
+        Some(path) => load_password_deny_list(path)?,
+        None => HashSet::new(),
+    };
+
+    Ok(PasswordPolicyModel {
+        min_length: policy.min_length,
+        require_lowercase: policy.require_lowercase,
+        require_uppercase: policy.require_uppercase,
+        require_digit: policy.require_digit,
+        require_symbol: policy.require_symbol,
+        denied_passwords,
+    })
+}
+
+fn load_password_deny_list(path: &str) -> Result<HashSet<String>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read password deny list {}", path))?;
+
+    Ok(content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_lowercase())
+        .collect())
+}
+
+fn read_file(path: &str) -> Result<Config> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read config {}", path))?;
+
+    toml::from_str(&content).with_context(|| format!("Failed to parse config {}", path))
+}
+
+fn apply_env_overrides(config: &mut Config) -> Result<()> {
+    override_from_env("SERVER_PORT", &mut config.server.port)?;
+    if let Ok(origins) = env::var("SERVER_CORS_ALLOWED_ORIGINS") {
+        config.server.cors_allowed_origins = origins
+            .split(',')
+            .map(|origin| origin.trim().to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+    }
+
+    override_from_env(
+        "SERVER_TRUST_FORWARDED_FOR",
+        &mut config.server.trust_forwarded_for,
+    )?;
+
+    override_from_env("JWT_ACCESS_SECRET", &mut config.jwt.access_secret)?;
+    override_from_env("JWT_REFRESH_SECRET", &mut config.jwt.refresh_secret)?;
+    override_from_env(
+        "JWT_ACCESS_TOKEN_TTL_SECS",
+        &mut config.jwt.access_token_ttl_secs,
+    )?;
+    override_from_env(
+        "JWT_REFRESH_TOKEN_TTL_SECS",
+        &mut config.jwt.refresh_token_ttl_secs,
+    )?;
+    override_from_env(
+        "JWT_COOKIE_MAX_AGE_SECS",
+        &mut config.jwt.cookie_max_age_secs,
+    )?;
+    override_from_env("JWT_ISSUER", &mut config.jwt.issuer)?;
+    override_from_env("JWT_AUDIENCE", &mut config.jwt.audience)?;
+    override_from_env("JWT_ALGORITHM", &mut config.jwt.algorithm)?;
+    override_from_env("JWT_SIGNING_KID", &mut config.jwt.signing_kid)?;
+
+    override_from_env("DATABASE_BACKEND", &mut config.database.backend)?;
+    override_from_env("DATABASE_URL", &mut config.database.url)?;
+    override_from_env(
+        "DATABASE_MAX_CONNECTIONS",
+        &mut config.database.max_connections,
+    )?;
+
+    override_from_env(
+        "PASSWORD_POLICY_MIN_LENGTH",
+        &mut config.password_policy.min_length,
+    )?;
+    if let Ok(path) = env::var("PASSWORD_POLICY_DENY_LIST_PATH") {
+        config.password_policy.deny_list_path = Some(path).filter(|p| !p.is_empty());
+    }
+
+    override_from_env("TOTP_ISSUER", &mut config.totp.issuer)?;
+    override_from_env(
+        "TOTP_CHALLENGE_TOKEN_TTL_SECS",
+        &mut config.totp.challenge_token_ttl_secs,
+    )?;
+
+    override_from_env("MAILER_FROM", &mut config.mailer.from)?;
+    override_from_env("MAILER_OUTBOX_DIR", &mut config.mailer.outbox_dir)?;
+
+    override_from_env("PASSWORD_RESET_URL", &mut config.password_reset.url)?;
+    override_from_env(
+        "PASSWORD_RESET_TOKEN_TTL_SECS",
+        &mut config.password_reset.token_ttl_secs,
+    )?;
+
+    override_from_env(
+        "EMAIL_VERIFICATION_REQUIRED",
+        &mut config.email_verification.required,
+    )?;
+    override_from_env("EMAIL_VERIFICATION_URL", &mut config.email_verification.url)?;
+    override_from_env(
+        "EMAIL_VERIFICATION_TOKEN_TTL_SECS",
+        &mut config.email_verification.token_ttl_secs,
+    )?;
+
+    if let Ok(origins) = env::var("CSRF_TRUSTED_ORIGINS") {
+        config.csrf.trusted_origins = origins
+            .split(',')
+            .map(|origin| origin.trim().to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+    }
+
+    override_from_env("OIDC_STATE_TTL_SECS", &mut config.oidc.state_ttl_secs)?;
+    if let Ok(url) = env::var("OIDC_LOGIN_REDIRECT_URL") {
+        config.oidc.login_redirect_url = Some(url).filter(|u| !u.is_empty());
+    }
+    // Provider secrets can stay out of the config file, e.g. OIDC_COMPANY_CLIENT_SECRET.
+    for provider in &mut config.oidc.providers {
+        let key = format!(
+            "OIDC_{}_CLIENT_SECRET",
+            provider.name.to_uppercase().replace('-', "_")
+        );
+        override_from_env(&key, &mut provider.client_secret)?;
+    }
+
+    override_from_env(
+        "LOGIN_THROTTLE_FREE_ATTEMPTS",
+        &mut config.login_throttle.free_attempts,
+    )?;
+    override_from_env(
+        "LOGIN_THROTTLE_BASE_DELAY_SECS",
+        &mut config.login_throttle.base_delay_secs,
+    )?;
+    override_from_env(
+        "LOGIN_THROTTLE_MAX_DELAY_SECS",
+        &mut config.login_throttle.max_delay_secs,
+    )?;
+    override_from_env(
+        "LOGIN_THROTTLE_LOCKOUT_ATTEMPTS",
+        &mut config.login_throttle.lockout_attempts,
+    )?;
+    override_from_env(
+        "LOGIN_THROTTLE_LOCKOUT_SECS",
+        &mut config.login_throttle.lockout_secs,
+    )?;
+
+    Ok(())
+}
+
+fn override_from_env<T>(key: &str, target: &mut T) -> Result<()>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    if let Ok(value) = env::var(key) {
+        *target = value
+            .parse()
+            .map_err(|e| anyhow!("Invalid value for {}: {}", key, e))?;
+    }
+
+    Ok(())
+}
+
+fn validate(config: &Config) -> Result<()> {
+    if config.server.port == 0 {
+        bail!("server.port must be greater than 0");
+    }
+    if config.server.cors_allowed_origins.is_empty() {
+        bail!("server.cors_allowed_origins must not be empty");
+    }
+
+    if config.jwt.access_secret.is_empty() || config.jwt.refresh_secret.is_empty() {
+        bail!("jwt.access_secret and jwt.refresh_secret must be set");
+    }
+    if config.jwt.access_secret == config.jwt.refresh_secret {
+        bail!("jwt.access_secret and jwt.refresh_secret must differ");
+    }
+    if config.jwt.access_token_ttl_secs <= 0
+        || config.jwt.refresh_token_ttl_secs <= 0
+        || config.jwt.cookie_max_age_secs <= 0
+    {
+        bail!("jwt token lifetimes and cookie max age must be greater than 0");
+    }
+    if config.jwt.issuer.is_empty() || config.jwt.audience.is_empty() {
+        bail!("jwt.issuer and jwt.audience must be set");
+    }
+    if config.jwt.algorithm != JwtAlgorithm::Hs256 {
+        let kids = config
+            .jwt
+            .keys
+            .iter()
+            .map(|k| &k.kid)
+            .collect::<HashSet<_>>();
+        if kids.len() != config.jwt.keys.len() || kids.iter().any(|kid| kid.is_empty()) {
+            bail!("jwt.keys must have unique, non-empty kids");
+        }
+
+        let has_signing_key = config
+            .jwt
+            .keys
+            .iter()
+            .any(|k| k.kid == config.jwt.signing_kid && k.private_key_path.is_some());
+        if !has_signing_key {
+            bail!("jwt.signing_kid must name a key in jwt.keys that has a private_key_path");
+        }
+    }
+
+    if config.database.backend != DatabaseBackend::Memory && config.database.url.is_empty() {
+        bail!(
+            "database.url must be set for the {:?} backend",
+            config.database.backend
+        );
+    }
+    if config.database.max_connections == 0 {
+        bail!("database.max_connections must be greater than 0");
+    }
+
+    if config.password_policy.min_length == 0 {
+        bail!("password_policy.min_length must be greater than 0");
+    }
+    if let Some(path) = &config.password_policy.deny_list_path
+        && !Path::new(path).is_file()
+    {
+        bail!("password_policy.deny_list_path {} does not exist", path);
+    }
+
+    if config.totp.issuer.is_empty() || config.totp.issuer.contains(':') {
+        bail!("totp.issuer must be set and must not contain ':'");
+    }
+    if config.totp.challenge_token_ttl_secs <= 0 {
+        bail!("totp.challenge_token_ttl_secs must be greater than 0");
+    }
+
+    if config.mailer.from.is_empty() || config.mailer.outbox_dir.is_empty() {
+        bail!("mailer.from and mailer.outbox_dir must be set");
+    }
+
+    if config.password_reset.url.is_empty() {
+        bail!("password_reset.url must be set");
+    }
+    if config.password_reset.token_ttl_secs <= 0 {
+        bail!("password_reset.token_ttl_secs must be greater than 0");
+    }
+
+    if config.email_verification.url.is_empty() {
+        bail!("email_verification.url must be set");
+    }
+    if config.email_verification.token_ttl_secs <= 0 {
+        bail!("email_verification.token_ttl_secs must be greater than 0");
+    }
+
+    if config.csrf.trusted_origins.is_empty()
+        || config.csrf.trusted_origins.iter().any(|o| o == "*")
+    {
+        bail!("csrf.trusted_origins must list explicit origins");
+    }
+
+    if config.oidc.state_ttl_secs <= 0 {
+        bail!("oidc.state_ttl_secs must be greater than 0");
+    }
+    let provider_names = config
+        .oidc
+        .providers
+        .iter()
+        .map(|p| &p.name)
+        .collect::<HashSet<_>>();
+    if provider_names.len() != config.oidc.providers.len() {
+        bail!("oidc.providers must have unique names");
+    }
+    for provider in &config.oidc.providers {
+        if provider.name.is_empty()
+            || !provider
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            bail!("oidc provider names may only contain a-z, 0-9 and '-'");
+        }
+        if provider.issuer.is_empty()
+            || provider.client_id.is_empty()
+            || provider.redirect_url.is_empty()
+        {
+            bail!(
+                "oidc provider {} needs an issuer, client_id and redirect_url",
+                provider.name
+            );
+        }
+        if !provider.scopes.iter().any(|s| s == "openid") {
+            bail!(
+                "oidc provider {} must request the openid scope",
+                provider.name
+            );
+        }
+    }
+
+    let throttle = &config.login_throttle;
+    if throttle.base_delay_secs == 0 || throttle.max_delay_secs < throttle.base_delay_secs {
+        bail!(
+            "login_throttle.max_delay_secs must be at least base_delay_secs, which must be greater than 0"
+        );
+    }
+    if throttle.lockout_attempts <= throttle.free_attempts || throttle.lockout_secs == 0 {
+        bail!(
+            "login_throttle.lockout_attempts must exceed free_attempts and lockout_secs must be greater than 0"
+        );
+    }
+
+    Ok(())
+}
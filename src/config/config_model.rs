@@ -0,0 +1,310 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub server: Server,
+    pub jwt: Jwt,
+    pub database: Database,
+    pub password_policy: PasswordPolicy,
+    pub totp: Totp,
+    pub login_throttle: LoginThrottle,
+    pub mailer: Mailer,
+    pub password_reset: PasswordReset,
+    pub email_verification: EmailVerification,
+    pub csrf: Csrf,
+    pub oidc: Oidc,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Server {
+    pub port: u16,
+    pub cors_allowed_origins: Vec<String>,
+    pub trust_forwarded_for: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Jwt {
+    pub access_secret: String,
+    pub refresh_secret: String,
+    pub access_token_ttl_secs: i64,
+    pub refresh_token_ttl_secs: i64,
+    pub cookie_max_age_secs: i64,
+    pub issuer: String,
+    pub audience: String,
+    pub algorithm: JwtAlgorithm,
+    pub signing_kid: String,
+    pub keys: Vec<JwtKey>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwtKey {
+    pub kid: String,
+    pub public_key_path: String,
+    pub private_key_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Database {
+    pub backend: DatabaseBackend,
+    pub url: String,
+    pub max_connections: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub deny_list_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Totp {
+    pub issuer: String,
+    pub challenge_token_ttl_secs: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoginThrottle {
+    pub free_attempts: u32,
+    pub base_delay_secs: u64,
+    pub max_delay_secs: u64,
+    pub lockout_attempts: u32,
+    pub lockout_secs: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Mailer {
+    pub from: String,
+    pub outbox_dir: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PasswordReset {
+    pub url: String,
+    pub token_ttl_secs: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EmailVerification {
+    pub required: bool,
+    pub url: String,
+    pub token_ttl_secs: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Csrf {
+    pub trusted_origins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Oidc {
+    pub state_ttl_secs: i64,
+    pub login_redirect_url: Option<String>,
+    pub providers: Vec<OidcProvider>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcProvider {
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    pub redirect_url: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+    #[serde(default = "default_true")]
+    pub auto_provision: bool,
+    #[serde(default)]
+    pub link_by_email: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    Memory,
+    Sqlite,
+    Postgres,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum JwtAlgorithm {
+    #[serde(rename = "HS256")]
+    Hs256,
+    #[serde(rename = "RS256")]
+    Rs256,
+    #[serde(rename = "EdDSA")]
+    EdDsa,
+}
+
+impl FromStr for JwtAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "HS256" => Ok(JwtAlgorithm::Hs256),
+            "RS256" => Ok(JwtAlgorithm::Rs256),
+            "EdDSA" => Ok(JwtAlgorithm::EdDsa),
+            other => Err(format!("unknown jwt algorithm {}", other)),
+        }
+    }
+}
+
+impl FromStr for DatabaseBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "memory" => Ok(DatabaseBackend::Memory),
+            "sqlite" => Ok(DatabaseBackend::Sqlite),
+            "postgres" => Ok(DatabaseBackend::Postgres),
+            other => Err(format!("unknown database backend {}", other)),
+        }
+    }
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self {
+            port: 3001,
+            cors_allowed_origins: vec!["*".to_string()],
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+impl Default for Jwt {
+    fn default() -> Self {
+        Self {
+            access_secret: String::new(),
+            refresh_secret: String::new(),
+            access_token_ttl_secs: 60 * 60 * 24,
+            refresh_token_ttl_secs: 60 * 60 * 24 * 7,
+            cookie_max_age_secs: 60 * 60 * 24 * 14,
+            issuer: "todos-api".to_string(),
+            audience: "todos-api".to_string(),
+            algorithm: JwtAlgorithm::Hs256,
+            signing_kid: String::new(),
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl Default for Database {
+    fn default() -> Self {
+        Self {
+            backend: DatabaseBackend::Memory,
+            url: String::new(),
+            max_connections: 10,
+        }
+    }
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            require_lowercase: true,
+            require_uppercase: true,
+            require_digit: true,
+            require_symbol: false,
+            deny_list_path: None,
+        }
+    }
+}
+
+impl Default for Totp {
+    fn default() -> Self {
+        Self {
+            issuer: "todos-api".to_string(),
+            challenge_token_ttl_secs: 60 * 5,
+        }
+    }
+}
+
+impl Default for LoginThrottle {
+    fn default() -> Self {
+        Self {
+            free_attempts: 3,
+            base_delay_secs: 1,
+            max_delay_secs: 60,
+            lockout_attempts: 10,
+            lockout_secs: 60 * 15,
+        }
+    }
+}
+
+impl Default for Mailer {
+    fn default() -> Self {
+        Self {
+            from: "todos-api <no-reply@localhost>".to_string(),
+            outbox_dir: "outbox".to_string(),
+        }
+    }
+}
+
+impl Default for PasswordReset {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:3000/reset-password".to_string(),
+            token_ttl_secs: 60 * 60,
+        }
+    }
+}
+
+impl Default for EmailVerification {
+    fn default() -> Self {
+        Self {
+            required: false,
+            url: "http://localhost:3001/users/verify".to_string(),
+            token_ttl_secs: 60 * 60 * 24,
+        }
+    }
+}
+
+impl Default for Csrf {
+    fn default() -> Self {
+        Self {
+            trusted_origins: vec!["http://localhost:3000".to_string()],
+        }
+    }
+}
+
+impl Default for Oidc {
+    fn default() -> Self {
+        Self {
+            state_ttl_secs: 60 * 10,
+            login_redirect_url: None,
+            providers: Vec::new(),
+        }
+    }
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "email".to_string(),
+        "profile".to_string(),
+    ]
+}
+
+fn default_true() -> bool {
+    true
+}
@@ -0,0 +1,2 @@
+pub mod config_loader;
+pub mod config_model;
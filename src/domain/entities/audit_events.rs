@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEventEntity {
+    pub id: String,
+    pub event: AuditEventKind,
+    pub actor_id: Option<String>,
+    pub target_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub details: Option<Value>,
+    pub created_at: NaiveDateTime,
+}
+
+// `actor_id` is the user who acted (or tried to), `target_id` the user acted upon when
+// that is someone else.
+#[derive(Debug, Clone)]
+pub struct AddAuditEventEntity {
+    pub event: AuditEventKind,
+    pub actor_id: Option<String>,
+    pub target_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub details: Option<Value>,
+}
+
+impl AddAuditEventEntity {
+    pub fn new(event: AuditEventKind) -> Self {
+        Self {
+            event,
+            actor_id: None,
+            target_id: None,
+            ip_address: None,
+            details: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditQueryEntity {
+    pub event: Option<AuditEventKind>,
+    pub actor_id: Option<String>,
+    pub created_after: Option<NaiveDateTime>,
+    pub created_before: Option<NaiveDateTime>,
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AuditEventKind {
+    #[serde(rename = "login.succeeded")]
+    LoginSucceeded,
+    #[serde(rename = "login.failed")]
+    LoginFailed,
+    #[serde(rename = "token.refreshed")]
+    TokenRefreshed,
+    #[serde(rename = "token.reuse_detected")]
+    TokenReuseDetected,
+    #[serde(rename = "logout")]
+    Logout,
+    #[serde(rename = "session.revoked")]
+    SessionRevoked,
+    #[serde(rename = "user.registered")]
+    UserRegistered,
+    #[serde(rename = "password.changed")]
+    PasswordChanged,
+    #[serde(rename = "password.reset")]
+    PasswordReset,
+    #[serde(rename = "role.changed")]
+    RoleChanged,
+    #[serde(rename = "account.deleted")]
+    AccountDeleted,
+}
+
+impl AuditEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventKind::LoginSucceeded => "login.succeeded",
+            AuditEventKind::LoginFailed => "login.failed",
+            AuditEventKind::TokenRefreshed => "token.refreshed",
+            AuditEventKind::TokenReuseDetected => "token.reuse_detected",
+            AuditEventKind::Logout => "logout",
+            AuditEventKind::SessionRevoked => "session.revoked",
+            AuditEventKind::UserRegistered => "user.registered",
+            AuditEventKind::PasswordChanged => "password.changed",
+            AuditEventKind::PasswordReset => "password.reset",
+            AuditEventKind::RoleChanged => "role.changed",
+            AuditEventKind::AccountDeleted => "account.deleted",
+        }
+    }
+}
+
+impl FromStr for AuditEventKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "login.succeeded" => Ok(AuditEventKind::LoginSucceeded),
+            "login.failed" => Ok(AuditEventKind::LoginFailed),
+            "token.refreshed" => Ok(AuditEventKind::TokenRefreshed),
+            "token.reuse_detected" => Ok(AuditEventKind::TokenReuseDetected),
+            "logout" => Ok(AuditEventKind::Logout),
+            "session.revoked" => Ok(AuditEventKind::SessionRevoked),
+            "user.registered" => Ok(AuditEventKind::UserRegistered),
+            "password.changed" => Ok(AuditEventKind::PasswordChanged),
+            "password.reset" => Ok(AuditEventKind::PasswordReset),
+            "role.changed" => Ok(AuditEventKind::RoleChanged),
+            "account.deleted" => Ok(AuditEventKind::AccountDeleted),
+            _ => Err(anyhow!("Unknown audit event: {}", s)),
+        }
+    }
+}
@@ -1,2 +1,9 @@
+pub mod audit_events;
+pub mod password_reset_tokens;
+pub mod personal_access_tokens;
+pub mod recovery_codes;
+pub mod refresh_tokens;
+pub mod sessions;
 pub mod todos;
+pub mod user_identities;
 pub mod users;
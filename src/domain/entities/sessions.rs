@@ -0,0 +1,15 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// One logged-in device. The id is the refresh token family id, so it stays the same
+// while the refresh token is rotated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEntity {
+    pub id: String,
+    #[serde(skip_serializing)]
+    pub user_id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: String,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: NaiveDateTime,
+}
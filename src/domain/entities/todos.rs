@@ -1,4 +1,6 @@
-use chrono::NaiveDateTime;
+use std::cmp::Ordering;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +9,7 @@ pub struct TodoEntity {
     pub title: String,
     pub user_id: String,
     pub completed: bool,
+    pub due_at: Option<DateTime<Utc>>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -14,4 +17,127 @@ pub struct TodoEntity {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AddTodoEntity {
     pub title: String,
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct UpdateTodoEntity {
+    pub title: Option<String>,
+    pub completed: Option<bool>,
+    // `Some(None)` clears the due date.
+    pub due_at: Option<Option<DateTime<Utc>>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Title,
+    DueAt,
+}
+
+impl TodoSortField {
+    pub fn column(&self) -> &'static str {
+        match self {
+            TodoSortField::CreatedAt => "created_at",
+            TodoSortField::UpdatedAt => "updated_at",
+            TodoSortField::Title => "title",
+            TodoSortField::DueAt => "due_at",
+        }
+    }
+
+    // Todos without a due date count as later than every dated one, which is also the
+    // order of the `(user_id, due_at, id)` index in both directions.
+    pub fn nulls(&self, direction: SortDirection) -> &'static str {
+        match (self, direction) {
+            (TodoSortField::DueAt, SortDirection::Asc) => " NULLS LAST",
+            (TodoSortField::DueAt, SortDirection::Desc) => " NULLS FIRST",
+            _ => "",
+        }
+    }
+
+    pub fn key_of(&self, todo: &TodoEntity) -> TodoSortKey {
+        match self {
+            TodoSortField::CreatedAt => TodoSortKey::CreatedAt(todo.created_at),
+            TodoSortField::UpdatedAt => TodoSortKey::UpdatedAt(todo.updated_at),
+            TodoSortField::Title => TodoSortKey::Title(todo.title.clone()),
+            TodoSortField::DueAt => TodoSortKey::DueAt(DueAtKey(todo.due_at)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum TodoSortKey {
+    CreatedAt(NaiveDateTime),
+    UpdatedAt(NaiveDateTime),
+    Title(String),
+    DueAt(DueAtKey),
+}
+
+impl TodoSortKey {
+    pub fn field(&self) -> TodoSortField {
+        match self {
+            TodoSortKey::CreatedAt(_) => TodoSortField::CreatedAt,
+            TodoSortKey::UpdatedAt(_) => TodoSortField::UpdatedAt,
+            TodoSortKey::Title(_) => TodoSortField::Title,
+            TodoSortKey::DueAt(_) => TodoSortField::DueAt,
+        }
+    }
+}
+
+// A due date that orders after every date when missing, matching `TodoSortField::nulls`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct DueAtKey(pub Option<DateTime<Utc>>);
+
+impl Ord for DueAtKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0, other.0) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+impl PartialOrd for DueAtKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TodoCursor {
+    pub key: TodoSortKey,
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TodoQueryEntity {
+    pub completed: Option<bool>,
+    pub created_after: Option<NaiveDateTime>,
+    pub created_before: Option<NaiveDateTime>,
+    // Inclusive lower and exclusive upper bound, so adjacent ranges don't overlap.
+    pub due_after: Option<DateTime<Utc>>,
+    pub due_before: Option<DateTime<Utc>>,
+    // Overdue means incomplete with a due date in the past.
+    pub overdue: Option<bool>,
+    // Title substring, ignoring ASCII case only. Case rules for other letters differ
+    // between databases and locales, so they are compared exactly on every backend.
+    pub q: Option<String>,
+    pub sort: TodoSortField,
+    pub direction: SortDirection,
+    pub cursor: Option<TodoCursor>,
+    pub limit: u32,
 }
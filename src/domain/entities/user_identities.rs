@@ -0,0 +1,11 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// Links an account at an external OpenID Connect provider to a local user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserIdentityEntity {
+    pub issuer: String,
+    pub subject: String,
+    pub user_id: String,
+    pub created_at: NaiveDateTime,
+}
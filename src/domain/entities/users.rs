@@ -1,3 +1,6 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
@@ -5,7 +8,19 @@ use serde::{Deserialize, Serialize};
 pub struct UserEntity {
     pub id: String,
     pub username: String,
-    pub password: String,
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub role: Role,
+    pub email: Option<String>,
+    pub verified: bool,
+    pub display_name: Option<String>,
+    pub timezone: String,
+    pub locale: String,
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    #[serde(skip_serializing)]
+    pub totp_last_step: Option<i64>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -13,5 +28,45 @@ pub struct UserEntity {
 #[derive(Debug, Clone)]
 pub struct RegisterUserEntity {
     pub username: String,
-    pub password: String,
+    pub password_hash: String,
+    pub role: Role,
+    pub email: Option<String>,
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateUserEntity {
+    pub username: Option<String>,
+    pub display_name: Option<Option<String>>,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    #[default]
+    User,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(Role::User),
+            "admin" => Ok(Role::Admin),
+            _ => Err(anyhow!("Unknown role: {}", s)),
+        }
+    }
 }
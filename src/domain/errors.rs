@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+use validator::ValidationErrors;
+
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, Error)]
+pub enum DomainError {
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("{message}")]
+    Validation {
+        message: String,
+        fields: FieldErrors,
+    },
+
+    #[error("{0}")]
+    Unauthorized(String),
+
+    #[error("{message}")]
+    TooManyRequests {
+        message: String,
+        retry_after_secs: u64,
+    },
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl DomainError {
+    pub fn validation(message: impl Into<String>) -> Self {
+        DomainError::Validation {
+            message: message.into(),
+            fields: FieldErrors::new(),
+        }
+    }
+
+    pub fn invalid_fields(fields: FieldErrors) -> Self {
+        DomainError::Validation {
+            message: "Validation failed".to_string(),
+            fields,
+        }
+    }
+}
+
+impl From<ValidationErrors> for DomainError {
+    fn from(errors: ValidationErrors) -> Self {
+        DomainError::invalid_fields(field_errors(&errors))
+    }
+}
+
+pub fn field_errors(errors: &ValidationErrors) -> FieldErrors {
+    errors
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            let messages = errors
+                .iter()
+                .map(|e| match &e.message {
+                    Some(message) => message.to_string(),
+                    None => e.code.to_string(),
+                })
+                .collect();
+
+            (field.to_string(), messages)
+        })
+        .collect()
+}
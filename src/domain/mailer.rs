@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+
+use crate::domain::errors::DomainError;
+
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer {
+    async fn send(&self, message: MailMessage) -> Result<(), DomainError>;
+}
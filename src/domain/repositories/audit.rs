@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+
+use crate::domain::{
+    entities::audit_events::{AddAuditEventEntity, AuditEventEntity, AuditQueryEntity},
+    errors::DomainError,
+};
+
+/// Audit events are append-only: there is no way to change or remove one.
+#[async_trait]
+pub trait AuditRepository {
+    async fn add(&self, event: AddAuditEventEntity) -> Result<(), DomainError>;
+    /// Matching events, newest first.
+    async fn query(&self, query: AuditQueryEntity) -> Result<Vec<AuditEventEntity>, DomainError>;
+}
@@ -1,13 +1,27 @@
-use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::domain::entities::todos::{AddTodoEntity, TodoEntity};
+use crate::domain::{
+    entities::todos::{AddTodoEntity, TodoEntity, TodoQueryEntity, UpdateTodoEntity},
+    errors::DomainError,
+};
 
 #[async_trait]
 pub trait TodosRepository {
-    async fn list(&self) -> Result<Vec<TodoEntity>>;
-    async fn get(&self, id: String) -> Result<TodoEntity>;
-    async fn add(&self, user_id: String, payload: AddTodoEntity) -> Result<TodoEntity>;
-    async fn to_completed(&self, id: String) -> Result<TodoEntity>;
-    async fn delete(&self, id: String) -> Result<()>;
+    async fn list(&self, user_id: String) -> Result<Vec<TodoEntity>, DomainError>;
+    async fn query(
+        &self,
+        user_id: String,
+        query: TodoQueryEntity,
+    ) -> Result<Vec<TodoEntity>, DomainError>;
+    async fn get(&self, user_id: String, id: String) -> Result<TodoEntity, DomainError>;
+    async fn add(&self, user_id: String, payload: AddTodoEntity)
+    -> Result<TodoEntity, DomainError>;
+    async fn update(
+        &self,
+        user_id: String,
+        id: String,
+        payload: UpdateTodoEntity,
+    ) -> Result<TodoEntity, DomainError>;
+    async fn to_completed(&self, user_id: String, id: String) -> Result<TodoEntity, DomainError>;
+    async fn delete(&self, user_id: String, id: String) -> Result<(), DomainError>;
 }
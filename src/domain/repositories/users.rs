@@ -1,11 +1,124 @@
-use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::domain::entities::users::{RegisterUserEntity, UserEntity};
+use crate::domain::{
+    entities::{
+        password_reset_tokens::PasswordResetTokenEntity,
+        personal_access_tokens::PersonalAccessTokenEntity,
+        recovery_codes::RecoveryCodeEntity,
+        refresh_tokens::RefreshTokenEntity,
+        sessions::SessionEntity,
+        user_identities::UserIdentityEntity,
+        users::{RegisterUserEntity, Role, UpdateUserEntity, UserEntity},
+    },
+    errors::DomainError,
+};
 
 #[async_trait]
 pub trait UsersRepository {
-    async fn register(&self, user: RegisterUserEntity) -> Result<()>;
-    async fn list(&self) -> Result<Vec<UserEntity>>;
-    async fn find_by_username(&self, username: String) -> Result<Option<UserEntity>>;
+    async fn register(&self, user: RegisterUserEntity) -> Result<(), DomainError>;
+    async fn list(&self) -> Result<Vec<UserEntity>, DomainError>;
+    async fn find_by_id(&self, id: String) -> Result<Option<UserEntity>, DomainError>;
+    async fn find_by_username(&self, username: String) -> Result<Option<UserEntity>, DomainError>;
+    async fn find_by_email(&self, email: String) -> Result<Vec<UserEntity>, DomainError>;
+    async fn update(
+        &self,
+        id: String,
+        payload: UpdateUserEntity,
+    ) -> Result<UserEntity, DomainError>;
+    /// Deletes the user along with their tokens, recovery codes and reset tokens.
+    async fn delete(&self, id: String) -> Result<(), DomainError>;
+    async fn update_role(&self, id: String, role: Role) -> Result<(), DomainError>;
+    async fn mark_verified(&self, id: String) -> Result<(), DomainError>;
+    async fn update_totp(
+        &self,
+        id: String,
+        secret: Option<String>,
+        enabled: bool,
+    ) -> Result<(), DomainError>;
+    /// Stores `step` as the last accepted TOTP step. Returns `false` if a step
+    /// at or after it was already used, so a code cannot be replayed.
+    async fn record_totp_step(&self, id: String, step: i64) -> Result<bool, DomainError>;
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: String,
+        codes: Vec<RecoveryCodeEntity>,
+    ) -> Result<(), DomainError>;
+    /// Marks the unused code as used. Returns `false` if no such code exists.
+    async fn use_recovery_code(
+        &self,
+        user_id: String,
+        code_hash: String,
+    ) -> Result<bool, DomainError>;
+    async fn update_password(&self, id: String, password_hash: String) -> Result<(), DomainError>;
+
+    async fn add_refresh_token(&self, token: RefreshTokenEntity) -> Result<(), DomainError>;
+    async fn find_refresh_token(
+        &self,
+        jti: String,
+    ) -> Result<Option<RefreshTokenEntity>, DomainError>;
+    /// Marks `jti` as rotated and stores `next` in its place. Returns `false`
+    /// without storing anything if `jti` was already rotated or revoked.
+    async fn rotate_refresh_token(
+        &self,
+        jti: String,
+        next: RefreshTokenEntity,
+    ) -> Result<bool, DomainError>;
+    async fn revoke_refresh_token_family(&self, family_id: String) -> Result<(), DomainError>;
+    async fn revoke_all_refresh_tokens(&self, user_id: String) -> Result<(), DomainError>;
+    async fn revoke_other_refresh_tokens(
+        &self,
+        user_id: String,
+        keep_family_id: String,
+    ) -> Result<(), DomainError>;
+
+    async fn add_session(&self, session: SessionEntity) -> Result<(), DomainError>;
+    async fn touch_session(
+        &self,
+        id: String,
+        ip_address: String,
+        user_agent: Option<String>,
+    ) -> Result<(), DomainError>;
+    /// Sessions that still have a usable refresh token, most recently used first.
+    async fn list_sessions(&self, user_id: String) -> Result<Vec<SessionEntity>, DomainError>;
+    /// Whether the session still has a usable refresh token, as `list_sessions` counts them.
+    async fn is_session_active(&self, id: String) -> Result<bool, DomainError>;
+
+    async fn add_password_reset_token(
+        &self,
+        token: PasswordResetTokenEntity,
+    ) -> Result<(), DomainError>;
+    /// Marks the token, and every other outstanding token of its user, as used.
+    /// Returns the user id, or `None` if the token is unknown, used or expired.
+    async fn consume_password_reset_token(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<String>, DomainError>;
+
+    async fn add_personal_access_token(
+        &self,
+        token: PersonalAccessTokenEntity,
+    ) -> Result<(), DomainError>;
+    async fn list_personal_access_tokens(
+        &self,
+        user_id: String,
+    ) -> Result<Vec<PersonalAccessTokenEntity>, DomainError>;
+    async fn find_personal_access_token(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<PersonalAccessTokenEntity>, DomainError>;
+    async fn touch_personal_access_token(&self, id: String) -> Result<(), DomainError>;
+    async fn revoke_personal_access_token(
+        &self,
+        user_id: String,
+        id: String,
+    ) -> Result<(), DomainError>;
+    async fn revoke_all_personal_access_tokens(&self, user_id: String) -> Result<(), DomainError>;
+
+    async fn add_identity(&self, identity: UserIdentityEntity) -> Result<(), DomainError>;
+    async fn find_by_identity(
+        &self,
+        issuer: String,
+        subject: String,
+    ) -> Result<Option<UserEntity>, DomainError>;
 }
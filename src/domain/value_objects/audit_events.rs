@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::domain::{
+    entities::audit_events::{AuditEventKind, AuditQueryEntity},
+    value_objects::todos::DEFAULT_PAGE_SIZE,
+};
+
+// Newest events come first; pass the oldest `created_at` seen as `created_before` for the next page.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct ListAuditEventsModel {
+    pub event: Option<AuditEventKind>,
+    pub actor_id: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<u32>,
+}
+
+impl ListAuditEventsModel {
+    pub fn to_entity(self) -> AuditQueryEntity {
+        AuditQueryEntity {
+            event: self.event,
+            actor_id: self.actor_id.filter(|a| !a.is_empty()),
+            created_after: self.created_after.map(|d| d.naive_utc()),
+            created_before: self.created_before.map(|d| d.naive_utc()),
+            limit: self.limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        }
+    }
+}
@@ -1,2 +1,16 @@
+pub mod audit_events;
+pub mod personal_access_tokens;
 pub mod todos;
+pub mod totp;
 pub mod users;
+
+use serde::{Deserialize, Deserializer};
+
+// With `#[serde(default)]`, tells a missing field (`None`) apart from `null` (`Some(None)`).
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,59 @@
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::domain::entities::personal_access_tokens::{PersonalAccessTokenEntity, TokenScope};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreatePersonalAccessTokenModel {
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "Name must be between 1 and 64 characters"
+    ))]
+    pub name: String,
+
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<TokenScope>,
+
+    #[validate(range(
+        min = 1,
+        max = 3650,
+        message = "Expiry must be between 1 and 3650 days"
+    ))]
+    pub expires_in_days: Option<i64>,
+}
+
+impl CreatePersonalAccessTokenModel {
+    pub fn to_entity(
+        self,
+        user_id: String,
+        token_hash: String,
+        now: NaiveDateTime,
+    ) -> PersonalAccessTokenEntity {
+        let mut scopes = self.scopes;
+        scopes.sort_by_key(|s| s.as_str());
+        scopes.dedup();
+
+        PersonalAccessTokenEntity {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            name: self.name,
+            token_hash,
+            scopes,
+            expires_at: self.expires_in_days.map(|days| now + Duration::days(days)),
+            last_used_at: None,
+            created_at: now,
+            revoked_at: None,
+        }
+    }
+}
+
+// The plaintext token is only ever returned here, right after creation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedPersonalAccessTokenModel {
+    pub token: String,
+    #[serde(flatten)]
+    pub details: PersonalAccessTokenEntity,
+}
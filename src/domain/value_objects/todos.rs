@@ -1,31 +1,157 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::domain::entities::todos::AddTodoEntity;
+use crate::domain::{
+    entities::todos::{
+        AddTodoEntity, SortDirection, TodoCursor, TodoEntity, TodoQueryEntity, TodoSortField,
+        UpdateTodoEntity,
+    },
+    errors::DomainError,
+    value_objects::present,
+};
+
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct AddTodoModel {
     #[validate(length(min = 1, message = "Title cannot be empty"))]
     pub title: String,
+
+    pub due_at: Option<DateTime<Utc>>,
 }
 
 impl AddTodoModel {
     pub fn to_entity(self) -> AddTodoEntity {
         AddTodoEntity {
             title: self.title.clone(),
+            due_at: self.due_at,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum TodoErrorMessage {
-    NotFound,
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct UpdateTodoModel {
+    #[validate(length(min = 1, message = "Title cannot be empty"))]
+    pub title: Option<String>,
+
+    pub completed: Option<bool>,
+
+    // A missing field keeps the due date, `null` clears it.
+    #[serde(default, deserialize_with = "present")]
+    pub due_at: Option<Option<DateTime<Utc>>>,
 }
 
-impl TodoErrorMessage {
-    pub fn to_string(&self) -> String {
-        match self {
-            TodoErrorMessage::NotFound => "NotFound".to_string(),
+impl UpdateTodoModel {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.completed.is_none() && self.due_at.is_none()
+    }
+
+    pub fn to_entity(self) -> UpdateTodoEntity {
+        UpdateTodoEntity {
+            title: self.title,
+            completed: self.completed,
+            due_at: self.due_at,
         }
     }
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct ListTodosModel {
+    pub completed: Option<bool>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub due_after: Option<DateTime<Utc>>,
+    pub due_before: Option<DateTime<Utc>>,
+    pub overdue: Option<bool>,
+    pub q: Option<String>,
+
+    #[serde(default)]
+    pub sort: TodoSortField,
+
+    #[serde(default)]
+    pub direction: SortDirection,
+
+    pub cursor: Option<String>,
+
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<u32>,
+}
+
+impl ListTodosModel {
+    pub fn to_entity(self) -> Result<TodoQueryEntity, DomainError> {
+        let cursor = self.cursor.as_deref().map(decode_cursor).transpose()?;
+
+        if let Some(cursor) = &cursor
+            && cursor.key.field() != self.sort
+        {
+            return Err(DomainError::validation(
+                "Cursor does not match the requested sort",
+            ));
+        }
+
+        Ok(TodoQueryEntity {
+            completed: self.completed,
+            created_after: self.created_after.map(|d| d.naive_utc()),
+            created_before: self.created_before.map(|d| d.naive_utc()),
+            due_after: self.due_after,
+            due_before: self.due_before,
+            overdue: self.overdue,
+            q: self.q.filter(|q| !q.is_empty()),
+            sort: self.sort,
+            direction: self.direction,
+            cursor,
+            limit: self.limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        })
+    }
+}
+
+// Query for `/todos/today` and `/todos/upcoming`, which fix the due range and sort by due date.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct TodoViewModel {
+    pub completed: Option<bool>,
+    pub cursor: Option<String>,
+
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<u32>,
+}
+
+impl TodoViewModel {
+    pub fn to_list_model(
+        self,
+        due_after: DateTime<Utc>,
+        due_before: DateTime<Utc>,
+    ) -> ListTodosModel {
+        ListTodosModel {
+            completed: self.completed,
+            due_after: Some(due_after),
+            due_before: Some(due_before),
+            sort: TodoSortField::DueAt,
+            direction: SortDirection::Asc,
+            cursor: self.cursor,
+            limit: self.limit,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TodoPageModel {
+    pub data: Vec<TodoEntity>,
+    pub next_cursor: Option<String>,
+}
+
+pub fn encode_cursor(cursor: &TodoCursor) -> String {
+    let json = serde_json::to_vec(cursor).unwrap_or_default();
+
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+fn decode_cursor(cursor: &str) -> Result<TodoCursor, DomainError> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(|| DomainError::validation("Invalid cursor"))
+}
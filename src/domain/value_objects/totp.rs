@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpCodeModel {
+    pub code: String,
+}
+
+// Either field proves the caller still owns the account; a code only counts once 2FA is on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpStepUpModel {
+    pub password: Option<String>,
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpEnrollmentModel {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+// Shown once; only hashes are stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryCodesModel {
+    pub recovery_codes: Vec<String>,
+}
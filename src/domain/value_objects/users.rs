@@ -1,22 +1,232 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use crate::domain::entities::users::RegisterUserEntity;
+use crate::domain::{
+    entities::users::{RegisterUserEntity, Role, UpdateUserEntity},
+    errors::{DomainError, field_errors},
+    value_objects::present,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct RegisterUserModel {
-    #[validate(length(min = 1, message = "Username cannot be empty"))]
+    #[validate(
+        length(
+            min = 3,
+            max = 32,
+            message = "Username must be between 3 and 32 characters"
+        ),
+        custom(function = "validate_username_charset")
+    )]
     pub username: String,
 
-    #[validate(length(min = 3, message = "Password cannot be empty, at least 3 characters"))]
+    #[validate(length(max = 128, message = "Password must be at most 128 characters"))]
     pub password: String,
+
+    #[validate(email(message = "Email must be a valid address"))]
+    pub email: Option<String>,
 }
 
 impl RegisterUserModel {
-    pub fn to_entity(self) -> RegisterUserEntity {
+    pub fn to_entity(
+        self,
+        password_hash: String,
+        role: Role,
+        verified: bool,
+    ) -> RegisterUserEntity {
         RegisterUserEntity {
             username: self.username.clone(),
-            password: self.password.clone(),
+            password_hash,
+            role,
+            email: self.email,
+            verified,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct UpdateProfileModel {
+    #[validate(
+        length(
+            min = 3,
+            max = 32,
+            message = "Username must be between 3 and 32 characters"
+        ),
+        custom(function = "validate_username_charset")
+    )]
+    pub username: Option<String>,
+
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "Display name must be between 1 and 64 characters"
+    ))]
+    // A missing field keeps the display name, `null` clears it.
+    #[serde(default, deserialize_with = "present")]
+    pub display_name: Option<Option<String>>,
+
+    #[validate(custom(function = "validate_timezone"))]
+    pub timezone: Option<String>,
+
+    #[validate(custom(function = "validate_locale"))]
+    pub locale: Option<String>,
+}
+
+impl UpdateProfileModel {
+    pub fn is_empty(&self) -> bool {
+        self.username.is_none()
+            && self.display_name.is_none()
+            && self.timezone.is_none()
+            && self.locale.is_none()
+    }
+
+    pub fn to_entity(self) -> UpdateUserEntity {
+        UpdateUserEntity {
+            username: self.username,
+            display_name: self.display_name,
+            timezone: self.timezone,
+            locale: self.locale,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateRoleModel {
+    pub role: Role,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ChangePasswordModel {
+    pub current_password: String,
+
+    #[validate(length(max = 128, message = "Password must be at most 128 characters"))]
+    pub new_password: String,
+
+    #[serde(default)]
+    pub revoke_personal_access_tokens: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyEmailModel {
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForgotPasswordModel {
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ResetPasswordModel {
+    pub token: String,
+
+    #[validate(length(max = 128, message = "Password must be at most 128 characters"))]
+    pub new_password: String,
+}
+
+fn validate_username_charset(username: &str) -> Result<(), ValidationError> {
+    if username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        Ok(())
+    } else {
+        Err(ValidationError::new("username_charset")
+            .with_message("Username may only contain letters, digits, '_', '-' and '.'".into()))
+    }
+}
+
+fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    match timezone.parse::<chrono_tz::Tz>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(ValidationError::new("timezone")
+            .with_message("Timezone must be an IANA name such as Europe/Berlin".into())),
+    }
+}
+
+// A BCP 47 tag shape check (`en`, `pt-BR`, `zh-Hant-TW`), not a registry lookup.
+fn validate_locale(locale: &str) -> Result<(), ValidationError> {
+    let mut subtags = locale.split('-');
+    let language = subtags.next().unwrap_or_default();
+
+    if (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags
+            .all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()))
+    {
+        Ok(())
+    } else {
+        Err(ValidationError::new("locale")
+            .with_message("Locale must be a language tag such as en or pt-BR".into()))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PasswordPolicyModel {
+    pub min_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub denied_passwords: HashSet<String>,
+}
+
+impl PasswordPolicyModel {
+    pub fn check(&self, password: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if password.chars().count() < self.min_length {
+            errors.push(format!(
+                "Password must be at least {} characters",
+                self.min_length
+            ));
+        }
+        if self.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+            errors.push("Password must contain a lowercase letter".to_string());
+        }
+        if self.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+            errors.push("Password must contain an uppercase letter".to_string());
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            errors.push("Password must contain a digit".to_string());
+        }
+        if self.require_symbol && password.chars().all(|c| c.is_alphanumeric()) {
+            errors.push("Password must contain a symbol".to_string());
+        }
+        if self.denied_passwords.contains(&password.to_lowercase()) {
+            errors.push("Password is too common".to_string());
+        }
+
+        errors
+    }
+
+    // Runs the model's own validation and adds the policy's complaints about
+    // `password` under `field`, so all problems come back together.
+    pub fn validate_with<T: Validate>(
+        &self,
+        model: &T,
+        field: &str,
+        password: &str,
+    ) -> Result<(), DomainError> {
+        let mut fields = model
+            .validate()
+            .err()
+            .map(|e| field_errors(&e))
+            .unwrap_or_default();
+
+        let password_errors = self.check(password);
+        if !password_errors.is_empty() {
+            fields
+                .entry(field.to_string())
+                .or_default()
+                .extend(password_errors);
+        }
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(DomainError::invalid_fields(fields))
         }
     }
 }
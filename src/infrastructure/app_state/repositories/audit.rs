@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::audit_events::{AddAuditEventEntity, AuditEventEntity, AuditQueryEntity},
+    errors::DomainError,
+    repositories::audit::AuditRepository,
+};
+
+#[derive(Clone)]
+pub struct AuditAppState {
+    audit_events: Arc<Mutex<Vec<AuditEventEntity>>>,
+}
+
+impl AuditAppState {
+    pub fn new() -> Self {
+        Self {
+            audit_events: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for AuditAppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AuditRepository for AuditAppState {
+    async fn add(&self, event: AddAuditEventEntity) -> Result<(), DomainError> {
+        self.audit_events.lock().unwrap().push(AuditEventEntity {
+            id: Uuid::new_v4().to_string(),
+            event: event.event,
+            actor_id: event.actor_id,
+            target_id: event.target_id,
+            ip_address: event.ip_address,
+            details: event.details,
+            created_at: chrono::Utc::now().naive_utc(),
+        });
+
+        Ok(())
+    }
+
+    async fn query(&self, query: AuditQueryEntity) -> Result<Vec<AuditEventEntity>, DomainError> {
+        Ok(self
+            .audit_events
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|e| query.event.is_none_or(|kind| e.event == kind))
+            .filter(|e| {
+                query
+                    .actor_id
+                    .as_ref()
+                    .is_none_or(|a| e.actor_id.as_ref() == Some(a))
+            })
+            .filter(|e| query.created_after.is_none_or(|d| e.created_at > d))
+            .filter(|e| query.created_before.is_none_or(|d| e.created_at < d))
+            .take(query.limit as usize)
+            .cloned()
+            .collect())
+    }
+}
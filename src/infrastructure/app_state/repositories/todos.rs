@@ -1,18 +1,22 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    cmp::Ordering,
+    sync::{Arc, Mutex},
+};
 
-use anyhow::Result;
 use async_trait::async_trait;
 use uuid::Uuid;
 
 use crate::domain::{
-    entities::todos::{AddTodoEntity, TodoEntity},
+    entities::todos::{
+        AddTodoEntity, SortDirection, TodoEntity, TodoQueryEntity, UpdateTodoEntity,
+    },
+    errors::DomainError,
     repositories::todos::TodosRepository,
-    value_objects::todos::TodoErrorMessage,
 };
 
 #[derive(Clone)]
 pub struct TodosAppState {
-    todos: Arc<Mutex<Vec<TodoEntity>>>,
+    pub(super) todos: Arc<Mutex<Vec<TodoEntity>>>,
 }
 
 impl TodosAppState {
@@ -23,32 +27,105 @@ impl TodosAppState {
     }
 }
 
+impl Default for TodosAppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl TodosRepository for TodosAppState {
-    async fn list(&self) -> Result<Vec<TodoEntity>> {
+    async fn list(&self, user_id: String) -> Result<Vec<TodoEntity>, DomainError> {
+        let todos = self.todos.lock().unwrap();
+
+        Ok(todos
+            .iter()
+            .filter(|todo| todo.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn query(
+        &self,
+        user_id: String,
+        query: TodoQueryEntity,
+    ) -> Result<Vec<TodoEntity>, DomainError> {
         let todos = self.todos.lock().unwrap();
-        Ok(todos.clone())
+        let q = query.q.as_ref().map(|q| q.to_ascii_lowercase());
+        let now = chrono::Utc::now();
+
+        let mut result: Vec<TodoEntity> = todos
+            .iter()
+            .filter(|todo| todo.user_id == user_id)
+            .filter(|todo| query.completed.is_none_or(|c| todo.completed == c))
+            .filter(|todo| query.created_after.is_none_or(|d| todo.created_at > d))
+            .filter(|todo| query.created_before.is_none_or(|d| todo.created_at < d))
+            .filter(|todo| {
+                query
+                    .due_after
+                    .is_none_or(|d| todo.due_at.is_some_and(|due| due >= d))
+            })
+            .filter(|todo| {
+                query
+                    .due_before
+                    .is_none_or(|d| todo.due_at.is_some_and(|due| due < d))
+            })
+            .filter(|todo| {
+                query.overdue.is_none_or(|overdue| {
+                    let is_overdue = !todo.completed && todo.due_at.is_some_and(|due| due < now);
+                    is_overdue == overdue
+                })
+            })
+            .filter(|todo| {
+                q.as_ref()
+                    .is_none_or(|q| todo.title.to_ascii_lowercase().contains(q))
+            })
+            .cloned()
+            .collect();
+
+        let compare = |todo: &TodoEntity, key: &_, id: &String| {
+            let ordering = query.sort.key_of(todo).cmp(key).then(todo.id.cmp(id));
+
+            match query.direction {
+                SortDirection::Asc => ordering,
+                SortDirection::Desc => ordering.reverse(),
+            }
+        };
+
+        if let Some(cursor) = &query.cursor {
+            result.retain(|todo| compare(todo, &cursor.key, &cursor.id) == Ordering::Greater);
+        }
+
+        result.sort_by(|a, b| compare(a, &query.sort.key_of(b), &b.id));
+        result.truncate(query.limit as usize);
+
+        Ok(result)
     }
 
-    async fn get(&self, id: String) -> Result<TodoEntity> {
+    async fn get(&self, user_id: String, id: String) -> Result<TodoEntity, DomainError> {
         let todos = self.todos.lock().unwrap();
 
         let result = todos
             .iter()
-            .find(|todo| todo.id == id)
+            .find(|todo| todo.id == id && todo.user_id == user_id)
             .cloned()
-            .ok_or_else(|| anyhow::anyhow!("{:?}", TodoErrorMessage::NotFound))?;
+            .ok_or_else(|| DomainError::NotFound("Todo not found".to_string()))?;
 
         Ok(result)
     }
 
-    async fn add(&self, user_id: String, payload: AddTodoEntity) -> Result<TodoEntity> {
+    async fn add(
+        &self,
+        user_id: String,
+        payload: AddTodoEntity,
+    ) -> Result<TodoEntity, DomainError> {
         let mut todos = self.todos.lock().unwrap();
 
         let new_todo = TodoEntity {
             id: Uuid::new_v4().to_string(),
             title: payload.title,
             completed: false,
+            due_at: payload.due_at,
             user_id,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
@@ -59,10 +136,39 @@ impl TodosRepository for TodosAppState {
         Ok(new_todo)
     }
 
-    async fn to_completed(&self, id: String) -> Result<TodoEntity> {
+    async fn update(
+        &self,
+        user_id: String,
+        id: String,
+        payload: UpdateTodoEntity,
+    ) -> Result<TodoEntity, DomainError> {
+        let mut todos = self.todos.lock().unwrap();
+
+        let todo = todos
+            .iter_mut()
+            .find(|todo| todo.id == id && todo.user_id == user_id)
+            .ok_or_else(|| DomainError::NotFound("Todo not found".to_string()))?;
+
+        if let Some(title) = payload.title {
+            todo.title = title;
+        }
+        if let Some(completed) = payload.completed {
+            todo.completed = completed;
+        }
+        if let Some(due_at) = payload.due_at {
+            todo.due_at = due_at;
+        }
+        todo.updated_at = chrono::Utc::now().naive_utc();
+
+        Ok(todo.clone())
+    }
+
+    async fn to_completed(&self, user_id: String, id: String) -> Result<TodoEntity, DomainError> {
         let mut todos = self.todos.lock().unwrap();
 
-        let todo = todos.iter_mut().find(|todo| todo.id == id);
+        let todo = todos
+            .iter_mut()
+            .find(|todo| todo.id == id && todo.user_id == user_id);
 
         match todo {
             Some(t) => {
@@ -70,21 +176,23 @@ impl TodosRepository for TodosAppState {
                 t.updated_at = chrono::Utc::now().naive_utc();
                 Ok(t.clone())
             }
-            None => return Err(anyhow::anyhow!("{:?}", TodoErrorMessage::NotFound)),
+            None => Err(DomainError::NotFound("Todo not found".to_string())),
         }
     }
 
-    async fn delete(&self, id: String) -> Result<()> {
+    async fn delete(&self, user_id: String, id: String) -> Result<(), DomainError> {
         let mut todos = self.todos.lock().unwrap();
 
-        let index = todos.iter().position(|todo| todo.id == id);
+        let index = todos
+            .iter()
+            .position(|todo| todo.id == id && todo.user_id == user_id);
 
         match index {
             Some(i) => {
                 todos.remove(i);
                 Ok(())
             }
-            None => Err(anyhow::anyhow!("{:?}", TodoErrorMessage::NotFound)),
+            None => Err(DomainError::NotFound("Todo not found".to_string())),
         }
     }
 }
@@ -1,53 +1,579 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    cmp::Reverse,
+    sync::{Arc, Mutex},
+};
 
-use anyhow::{Ok, Result};
 use async_trait::async_trait;
 use uuid::Uuid;
 
 use crate::domain::{
-    entities::users::{RegisterUserEntity, UserEntity},
+    entities::{
+        password_reset_tokens::PasswordResetTokenEntity,
+        personal_access_tokens::PersonalAccessTokenEntity,
+        recovery_codes::RecoveryCodeEntity,
+        refresh_tokens::RefreshTokenEntity,
+        sessions::SessionEntity,
+        todos::TodoEntity,
+        user_identities::UserIdentityEntity,
+        users::{RegisterUserEntity, Role, UpdateUserEntity, UserEntity},
+    },
+    errors::DomainError,
     repositories::users::UsersRepository,
 };
 
+use super::todos::TodosAppState;
+
 #[derive(Clone)]
 pub struct UsersAppState {
     users: Arc<Mutex<Vec<UserEntity>>>,
+    refresh_tokens: Arc<Mutex<Vec<RefreshTokenEntity>>>,
+    personal_access_tokens: Arc<Mutex<Vec<PersonalAccessTokenEntity>>>,
+    recovery_codes: Arc<Mutex<Vec<RecoveryCodeEntity>>>,
+    password_reset_tokens: Arc<Mutex<Vec<PasswordResetTokenEntity>>>,
+    identities: Arc<Mutex<Vec<UserIdentityEntity>>>,
+    sessions: Arc<Mutex<Vec<SessionEntity>>>,
+    todos: Arc<Mutex<Vec<TodoEntity>>>,
 }
 
 impl UsersAppState {
-    pub fn new() -> Self {
+    // Shares the todo list so deleting a user removes their todos, as `ON DELETE CASCADE` does.
+    pub fn new(todos: &TodosAppState) -> Self {
         Self {
             users: Arc::new(Mutex::new(Vec::new())),
+            refresh_tokens: Arc::new(Mutex::new(Vec::new())),
+            personal_access_tokens: Arc::new(Mutex::new(Vec::new())),
+            recovery_codes: Arc::new(Mutex::new(Vec::new())),
+            password_reset_tokens: Arc::new(Mutex::new(Vec::new())),
+            identities: Arc::new(Mutex::new(Vec::new())),
+            sessions: Arc::new(Mutex::new(Vec::new())),
+            todos: Arc::clone(&todos.todos),
         }
     }
 }
 
 #[async_trait]
 impl UsersRepository for UsersAppState {
-    async fn register(&self, payload: RegisterUserEntity) -> Result<()> {
+    async fn register(&self, payload: RegisterUserEntity) -> Result<(), DomainError> {
+        let mut users = self.users.lock().unwrap();
+
+        if users
+            .iter()
+            .any(|u| u.username.to_lowercase() == payload.username.to_lowercase())
+        {
+            return Err(DomainError::Conflict("Username already exists".to_string()));
+        }
+
         let user = UserEntity {
             id: Uuid::new_v4().to_string(),
             username: payload.username,
-            password: payload.password, // In a real application, ensure to hash the password
+            password_hash: payload.password_hash,
+            role: payload.role,
+            email: payload.email,
+            verified: payload.verified,
+            display_name: None,
+            timezone: "UTC".to_string(),
+            locale: "en".to_string(),
+            totp_secret: None,
+            totp_enabled: false,
+            totp_last_step: None,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
         };
 
-        self.users.lock().unwrap().push(user);
+        users.push(user);
 
         Ok(())
     }
 
-    async fn list(&self) -> Result<Vec<UserEntity>> {
+    async fn list(&self) -> Result<Vec<UserEntity>, DomainError> {
         let users = self.users.lock().unwrap();
 
         Ok(users.clone())
     }
 
-    async fn find_by_username(&self, username: String) -> Result<Option<UserEntity>> {
+    async fn find_by_id(&self, id: String) -> Result<Option<UserEntity>, DomainError> {
         let users = self.users.lock().unwrap();
-        let user = users.iter().find(|u| u.username == username).cloned();
+        let user = users.iter().find(|u| u.id == id).cloned();
 
         Ok(user)
     }
+
+    async fn find_by_username(&self, username: String) -> Result<Option<UserEntity>, DomainError> {
+        let users = self.users.lock().unwrap();
+        let user = users
+            .iter()
+            .find(|u| u.username.to_lowercase() == username.to_lowercase())
+            .cloned();
+
+        Ok(user)
+    }
+
+    async fn find_by_email(&self, email: String) -> Result<Vec<UserEntity>, DomainError> {
+        let users = self.users.lock().unwrap();
+
+        Ok(users
+            .iter()
+            .filter(|u| {
+                u.email
+                    .as_ref()
+                    .is_some_and(|e| e.to_lowercase() == email.to_lowercase())
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn update(
+        &self,
+        id: String,
+        payload: UpdateUserEntity,
+    ) -> Result<UserEntity, DomainError> {
+        let mut users = self.users.lock().unwrap();
+
+        if let Some(username) = &payload.username
+            && users
+                .iter()
+                .any(|u| u.id != id && u.username.to_lowercase() == username.to_lowercase())
+        {
+            return Err(DomainError::Conflict("Username already exists".to_string()));
+        }
+
+        let user = users
+            .iter_mut()
+            .find(|u| u.id == id)
+            .ok_or_else(|| DomainError::NotFound("User not found".to_string()))?;
+
+        if let Some(username) = payload.username {
+            user.username = username;
+        }
+        if let Some(display_name) = payload.display_name {
+            user.display_name = display_name;
+        }
+        if let Some(timezone) = payload.timezone {
+            user.timezone = timezone;
+        }
+        if let Some(locale) = payload.locale {
+            user.locale = locale;
+        }
+        user.updated_at = chrono::Utc::now().naive_utc();
+
+        Ok(user.clone())
+    }
+
+    async fn delete(&self, id: String) -> Result<(), DomainError> {
+        let mut users = self.users.lock().unwrap();
+
+        let Some(index) = users.iter().position(|u| u.id == id) else {
+            return Err(DomainError::NotFound("User not found".to_string()));
+        };
+        users.remove(index);
+
+        self.refresh_tokens
+            .lock()
+            .unwrap()
+            .retain(|t| t.user_id != id);
+        self.personal_access_tokens
+            .lock()
+            .unwrap()
+            .retain(|t| t.user_id != id);
+        self.recovery_codes
+            .lock()
+            .unwrap()
+            .retain(|c| c.user_id != id);
+        self.password_reset_tokens
+            .lock()
+            .unwrap()
+            .retain(|t| t.user_id != id);
+        self.identities.lock().unwrap().retain(|i| i.user_id != id);
+        self.sessions.lock().unwrap().retain(|s| s.user_id != id);
+        self.todos.lock().unwrap().retain(|t| t.user_id != id);
+
+        Ok(())
+    }
+
+    async fn update_password(&self, id: String, password_hash: String) -> Result<(), DomainError> {
+        let mut users = self.users.lock().unwrap();
+
+        if let Some(user) = users.iter_mut().find(|u| u.id == id) {
+            user.password_hash = password_hash;
+            user.updated_at = chrono::Utc::now().naive_utc();
+        }
+
+        Ok(())
+    }
+
+    async fn update_role(&self, id: String, role: Role) -> Result<(), DomainError> {
+        let mut users = self.users.lock().unwrap();
+
+        match users.iter_mut().find(|u| u.id == id) {
+            Some(user) => {
+                user.role = role;
+                user.updated_at = chrono::Utc::now().naive_utc();
+
+                Ok(())
+            }
+            None => Err(DomainError::NotFound("User not found".to_string())),
+        }
+    }
+
+    async fn mark_verified(&self, id: String) -> Result<(), DomainError> {
+        let mut users = self.users.lock().unwrap();
+
+        match users.iter_mut().find(|u| u.id == id) {
+            Some(user) => {
+                user.verified = true;
+                user.updated_at = chrono::Utc::now().naive_utc();
+
+                Ok(())
+            }
+            None => Err(DomainError::NotFound("User not found".to_string())),
+        }
+    }
+
+    async fn update_totp(
+        &self,
+        id: String,
+        secret: Option<String>,
+        enabled: bool,
+    ) -> Result<(), DomainError> {
+        let mut users = self.users.lock().unwrap();
+
+        if let Some(user) = users.iter_mut().find(|u| u.id == id) {
+            user.totp_secret = secret;
+            user.totp_enabled = enabled;
+            user.updated_at = chrono::Utc::now().naive_utc();
+        }
+
+        Ok(())
+    }
+
+    async fn record_totp_step(&self, id: String, step: i64) -> Result<bool, DomainError> {
+        let mut users = self.users.lock().unwrap();
+
+        match users.iter_mut().find(|u| u.id == id) {
+            Some(user) if user.totp_last_step.is_none_or(|last| last < step) => {
+                user.totp_last_step = Some(step);
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: String,
+        codes: Vec<RecoveryCodeEntity>,
+    ) -> Result<(), DomainError> {
+        let mut recovery_codes = self.recovery_codes.lock().unwrap();
+
+        recovery_codes.retain(|c| c.user_id != user_id);
+        recovery_codes.extend(codes);
+
+        Ok(())
+    }
+
+    async fn use_recovery_code(
+        &self,
+        user_id: String,
+        code_hash: String,
+    ) -> Result<bool, DomainError> {
+        let mut recovery_codes = self.recovery_codes.lock().unwrap();
+
+        match recovery_codes
+            .iter_mut()
+            .find(|c| c.user_id == user_id && c.code_hash == code_hash && c.used_at.is_none())
+        {
+            Some(code) => {
+                code.used_at = Some(chrono::Utc::now().naive_utc());
+
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn add_refresh_token(&self, token: RefreshTokenEntity) -> Result<(), DomainError> {
+        self.refresh_tokens.lock().unwrap().push(token);
+
+        Ok(())
+    }
+
+    async fn find_refresh_token(
+        &self,
+        jti: String,
+    ) -> Result<Option<RefreshTokenEntity>, DomainError> {
+        let refresh_tokens = self.refresh_tokens.lock().unwrap();
+
+        Ok(refresh_tokens.iter().find(|t| t.jti == jti).cloned())
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        jti: String,
+        next: RefreshTokenEntity,
+    ) -> Result<bool, DomainError> {
+        let mut refresh_tokens = self.refresh_tokens.lock().unwrap();
+
+        match refresh_tokens
+            .iter_mut()
+            .find(|t| t.jti == jti && t.rotated_at.is_none() && t.revoked_at.is_none())
+        {
+            Some(current) => {
+                current.rotated_at = Some(chrono::Utc::now().naive_utc());
+                refresh_tokens.push(next);
+
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn revoke_refresh_token_family(&self, family_id: String) -> Result<(), DomainError> {
+        let now = chrono::Utc::now().naive_utc();
+
+        self.refresh_tokens
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|t| t.family_id == family_id && t.revoked_at.is_none())
+            .for_each(|t| t.revoked_at = Some(now));
+
+        Ok(())
+    }
+
+    async fn revoke_all_refresh_tokens(&self, user_id: String) -> Result<(), DomainError> {
+        let now = chrono::Utc::now().naive_utc();
+
+        self.refresh_tokens
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|t| t.user_id == user_id && t.revoked_at.is_none())
+            .for_each(|t| t.revoked_at = Some(now));
+
+        Ok(())
+    }
+
+    async fn revoke_other_refresh_tokens(
+        &self,
+        user_id: String,
+        keep_family_id: String,
+    ) -> Result<(), DomainError> {
+        let now = chrono::Utc::now().naive_utc();
+
+        for token in self.refresh_tokens.lock().unwrap().iter_mut().filter(|t| {
+            t.user_id == user_id && t.family_id != keep_family_id && t.revoked_at.is_none()
+        }) {
+            token.revoked_at = Some(now);
+        }
+
+        Ok(())
+    }
+
+    async fn add_session(&self, session: SessionEntity) -> Result<(), DomainError> {
+        self.sessions.lock().unwrap().push(session);
+
+        Ok(())
+    }
+
+    async fn touch_session(
+        &self,
+        id: String,
+        ip_address: String,
+        user_agent: Option<String>,
+    ) -> Result<(), DomainError> {
+        if let Some(session) = self
+            .sessions
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|s| s.id == id)
+        {
+            session.ip_address = ip_address;
+            session.user_agent = user_agent;
+            session.last_used_at = chrono::Utc::now().naive_utc();
+        }
+
+        Ok(())
+    }
+
+    async fn list_sessions(&self, user_id: String) -> Result<Vec<SessionEntity>, DomainError> {
+        let now = chrono::Utc::now().naive_utc();
+        let refresh_tokens = self.refresh_tokens.lock().unwrap();
+
+        let mut sessions = self
+            .sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| {
+                s.user_id == user_id
+                    && refresh_tokens.iter().any(|t| {
+                        t.family_id == s.id
+                            && t.rotated_at.is_none()
+                            && t.revoked_at.is_none()
+                            && t.expires_at > now
+                    })
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        sessions.sort_by_key(|s| Reverse(s.last_used_at));
+
+        Ok(sessions)
+    }
+
+    async fn is_session_active(&self, id: String) -> Result<bool, DomainError> {
+        let now = chrono::Utc::now().naive_utc();
+
+        Ok(self.refresh_tokens.lock().unwrap().iter().any(|t| {
+            t.family_id == id
+                && t.rotated_at.is_none()
+                && t.revoked_at.is_none()
+                && t.expires_at > now
+        }))
+    }
+
+    async fn add_password_reset_token(
+        &self,
+        token: PasswordResetTokenEntity,
+    ) -> Result<(), DomainError> {
+        self.password_reset_tokens.lock().unwrap().push(token);
+
+        Ok(())
+    }
+
+    async fn consume_password_reset_token(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<String>, DomainError> {
+        let mut tokens = self.password_reset_tokens.lock().unwrap();
+        let now = chrono::Utc::now().naive_utc();
+
+        let Some(user_id) = tokens
+            .iter()
+            .find(|t| t.token_hash == token_hash && t.used_at.is_none() && t.expires_at > now)
+            .map(|t| t.user_id.clone())
+        else {
+            return Ok(None);
+        };
+
+        for token in tokens
+            .iter_mut()
+            .filter(|t| t.user_id == user_id && t.used_at.is_none())
+        {
+            token.used_at = Some(now);
+        }
+
+        Ok(Some(user_id))
+    }
+
+    async fn add_personal_access_token(
+        &self,
+        token: PersonalAccessTokenEntity,
+    ) -> Result<(), DomainError> {
+        self.personal_access_tokens.lock().unwrap().push(token);
+
+        Ok(())
+    }
+
+    async fn list_personal_access_tokens(
+        &self,
+        user_id: String,
+    ) -> Result<Vec<PersonalAccessTokenEntity>, DomainError> {
+        let tokens = self.personal_access_tokens.lock().unwrap();
+
+        Ok(tokens
+            .iter()
+            .filter(|t| t.user_id == user_id && t.revoked_at.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn find_personal_access_token(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<PersonalAccessTokenEntity>, DomainError> {
+        let tokens = self.personal_access_tokens.lock().unwrap();
+        let token = tokens.iter().find(|t| t.token_hash == token_hash).cloned();
+
+        Ok(token)
+    }
+
+    async fn touch_personal_access_token(&self, id: String) -> Result<(), DomainError> {
+        let mut tokens = self.personal_access_tokens.lock().unwrap();
+
+        if let Some(token) = tokens.iter_mut().find(|t| t.id == id) {
+            token.last_used_at = Some(chrono::Utc::now().naive_utc());
+        }
+
+        Ok(())
+    }
+
+    async fn revoke_personal_access_token(
+        &self,
+        user_id: String,
+        id: String,
+    ) -> Result<(), DomainError> {
+        let mut tokens = self.personal_access_tokens.lock().unwrap();
+
+        match tokens
+            .iter_mut()
+            .find(|t| t.id == id && t.user_id == user_id && t.revoked_at.is_none())
+        {
+            Some(token) => {
+                token.revoked_at = Some(chrono::Utc::now().naive_utc());
+
+                Ok(())
+            }
+            None => Err(DomainError::NotFound("Token not found".to_string())),
+        }
+    }
+
+    async fn revoke_all_personal_access_tokens(&self, user_id: String) -> Result<(), DomainError> {
+        let now = chrono::Utc::now().naive_utc();
+
+        self.personal_access_tokens
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|t| t.user_id == user_id && t.revoked_at.is_none())
+            .for_each(|t| t.revoked_at = Some(now));
+
+        Ok(())
+    }
+
+    async fn add_identity(&self, identity: UserIdentityEntity) -> Result<(), DomainError> {
+        let mut identities = self.identities.lock().unwrap();
+
+        if identities
+            .iter()
+            .any(|i| i.issuer == identity.issuer && i.subject == identity.subject)
+        {
+            return Err(DomainError::Conflict(
+                "Identity is already linked".to_string(),
+            ));
+        }
+
+        identities.push(identity);
+
+        Ok(())
+    }
+
+    async fn find_by_identity(
+        &self,
+        issuer: String,
+        subject: String,
+    ) -> Result<Option<UserEntity>, DomainError> {
+        let user_id = self
+            .identities
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|i| i.issuer == issuer && i.subject == subject)
+            .map(|i| i.user_id.clone());
+
+        let users = self.users.lock().unwrap();
+
+        Ok(user_id.and_then(|user_id| users.iter().find(|u| u.id == user_id).cloned()))
+    }
 }
@@ -0,0 +1,66 @@
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
+use argon2::{
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+
+// Raising any of these makes existing hashes eligible for a rehash on the next login.
+const MEMORY_COST: u32 = 19 * 1024;
+const TIME_COST: u32 = 2;
+const PARALLELISM: u32 = 1;
+
+// Verified against when a username does not exist, so that path costs the same
+// argon2 work as a wrong password.
+static DUMMY_HASH: LazyLock<String> =
+    LazyLock::new(|| hash("dummy password").expect("argon2 params are valid"));
+
+fn argon2() -> Result<Argon2<'static>> {
+    let params = Params::new(MEMORY_COST, TIME_COST, PARALLELISM, None)
+        .map_err(|e| anyhow!("Invalid argon2 params: {}", e))?;
+
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+}
+
+pub fn hash(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    let password_hash = argon2()?
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| anyhow!("Failed to hash password: {}", e))?;
+
+    Ok(password_hash.to_string())
+}
+
+pub fn verify(password: &str, password_hash: &str) -> Result<bool> {
+    let parsed_hash =
+        PasswordHash::new(password_hash).map_err(|e| anyhow!("Invalid password hash: {}", e))?;
+
+    // The comparison of the derived output is done in constant time by `password_hash`.
+    Ok(argon2()?
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
+
+pub fn dummy_hash() -> &'static str {
+    &DUMMY_HASH
+}
+
+pub fn needs_rehash(password_hash: &str) -> Result<bool> {
+    let parsed_hash =
+        PasswordHash::new(password_hash).map_err(|e| anyhow!("Invalid password hash: {}", e))?;
+
+    if parsed_hash.algorithm != Algorithm::Argon2id.ident()
+        || parsed_hash.version != Some(Version::V0x13.into())
+    {
+        return Ok(true);
+    }
+
+    let params = Params::try_from(&parsed_hash)
+        .map_err(|e| anyhow!("Invalid password hash params: {}", e))?;
+
+    Ok(params.m_cost() != MEMORY_COST
+        || params.t_cost() != TIME_COST
+        || params.p_cost() != PARALLELISM)
+}
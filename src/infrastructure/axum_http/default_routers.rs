@@ -1,9 +1,11 @@
 use axum::{http::StatusCode, response::IntoResponse};
 
+use crate::domain::errors::DomainError;
+
 pub async fn health_check() -> impl IntoResponse {
     (StatusCode::OK, "OK").into_response()
 }
 
 pub async fn not_found() -> impl IntoResponse {
-    (StatusCode::NOT_FOUND, "Not Found").into_response()
+    DomainError::NotFound("Not Found".to_string()).into_response()
 }
@@ -0,0 +1,50 @@
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use tracing::error;
+
+use crate::domain::errors::DomainError;
+
+impl IntoResponse for DomainError {
+    fn into_response(self) -> Response {
+        let (status, code) = match &self {
+            DomainError::NotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
+            DomainError::Conflict(_) => (StatusCode::CONFLICT, "conflict"),
+            DomainError::Forbidden(_) => (StatusCode::FORBIDDEN, "forbidden"),
+            DomainError::Validation { .. } => (StatusCode::BAD_REQUEST, "validation"),
+            DomainError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "unauthorized"),
+            DomainError::TooManyRequests { .. } => {
+                (StatusCode::TOO_MANY_REQUESTS, "too_many_requests")
+            }
+            DomainError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal"),
+        };
+
+        let message = match &self {
+            DomainError::Internal(e) => {
+                error!("Internal error: {:?}", e);
+                "Internal Server Error".to_string()
+            }
+            e => e.to_string(),
+        };
+
+        match self {
+            DomainError::Validation { fields, .. } if !fields.is_empty() => (
+                status,
+                Json(json!({ "error": message, "code": code, "fields": fields })),
+            )
+                .into_response(),
+            DomainError::TooManyRequests {
+                retry_after_secs, ..
+            } => (
+                status,
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                Json(json!({ "error": message, "code": code })),
+            )
+                .into_response(),
+            _ => (status, Json(json!({ "error": message, "code": code }))).into_response(),
+        }
+    }
+}
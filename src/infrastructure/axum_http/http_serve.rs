@@ -1,51 +1,247 @@
 use std::{net::SocketAddr, sync::Arc};
 
 use anyhow::{Ok, Result};
-use axum::{Router, http::Method, routing::get};
+use axum::{
+    Router,
+    http::{HeaderName, HeaderValue, Method, header},
+    middleware,
+    routing::get,
+};
 use tokio::net::TcpListener;
 use tower_http::{
-    cors::{Any, CorsLayer},
+    cors::{AllowOrigin, Any, CorsLayer},
     trace::TraceLayer,
 };
 use tracing::info;
 
-use crate::infrastructure::{
-    app_state::repositories::users::UsersAppState,
-    axum_http::{default_routers, routers},
+use crate::{
+    application::usecases::personal_access_tokens::PersonalAccessTokensUseCase,
+    config::{
+        config_loader,
+        config_model::{Config, DatabaseBackend},
+    },
+    domain::{
+        mailer::Mailer,
+        repositories::{audit::AuditRepository, todos::TodosRepository, users::UsersRepository},
+    },
+    infrastructure::{
+        app_state::repositories::{
+            audit::AuditAppState, todos::TodosAppState, users::UsersAppState,
+        },
+        axum_http::{
+            default_routers,
+            middleware::{client_ip, csrf_origin_check},
+            routers,
+        },
+        file_mailer::FileMailer,
+        jwt_authentication::jwt_keys::JwtKeys,
+        login_throttle::LoginThrottle,
+        postgres::{
+            self,
+            repositories::{audit::AuditPostgres, todos::TodosPostgres, users::UsersPostgres},
+        },
+        sqlite::{
+            self,
+            repositories::{audit::AuditSqlite, todos::TodosSqlite, users::UsersSqlite},
+        },
+    },
 };
 
-pub async fn start() -> Result<()> {
-    let user_app_state = Arc::new(UsersAppState::new());
+pub async fn start(config: Arc<Config>) -> Result<()> {
+    let mailer = Arc::new(FileMailer::new(
+        &config.mailer.outbox_dir,
+        &config.mailer.from,
+    ));
+
+    let app = match config.database.backend {
+        DatabaseBackend::Memory => {
+            let todos_repository = TodosAppState::new();
+            let users_repository = UsersAppState::new(&todos_repository);
+
+            app(
+                Arc::clone(&config),
+                Arc::new(todos_repository),
+                Arc::new(users_repository),
+                Arc::new(AuditAppState::new()),
+                mailer,
+            )?
+        }
+        DatabaseBackend::Sqlite => {
+            let pool = sqlite::connect(&config.database.url).await?;
+
+            app(
+                Arc::clone(&config),
+                Arc::new(TodosSqlite::new(pool.clone())),
+                Arc::new(UsersSqlite::new(pool.clone())),
+                Arc::new(AuditSqlite::new(pool)),
+                mailer,
+            )?
+        }
+        DatabaseBackend::Postgres => {
+            let pool =
+                postgres::connect(&config.database.url, config.database.max_connections).await?;
+
+            app(
+                Arc::clone(&config),
+                Arc::new(TodosPostgres::new(pool.clone())),
+                Arc::new(UsersPostgres::new(pool.clone())),
+                Arc::new(AuditPostgres::new(pool)),
+                mailer,
+            )?
+        }
+    };
+
+    info!("Using {:?} database backend", config.database.backend);
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.server.port));
+    let listener = TcpListener::bind(addr).await?;
+
+    info!("Server is running on port {}", config.server.port);
+
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
+
+    Ok(())
+}
+
+// Builds the routes without binding a listener, so tests can serve them on their own.
+pub fn app<T, U, A, M>(
+    config: Arc<Config>,
+    todos_repository: Arc<T>,
+    users_repository: Arc<U>,
+    audit_repository: Arc<A>,
+    mailer: Arc<M>,
+) -> Result<Router>
+where
+    T: TodosRepository + Send + Sync + 'static,
+    U: UsersRepository + Send + Sync + 'static,
+    A: AuditRepository + Send + Sync + 'static,
+    M: Mailer + Send + Sync + 'static,
+{
+    let password_policy = Arc::new(config_loader::load_password_policy(&config)?);
+    let jwt_keys = Arc::new(JwtKeys::load(&config.jwt)?);
+    let authentication = (
+        Arc::new(PersonalAccessTokensUseCase::new(Arc::clone(
+            &users_repository,
+        ))),
+        Arc::clone(&jwt_keys),
+        Arc::clone(&users_repository),
+    );
+    // Shared so every code and password check counts against the same limits.
+    let login_throttle = Arc::new(LoginThrottle::new(config.login_throttle.clone()));
 
     let app = Router::new()
         .fallback(default_routers::not_found)
-        .nest("/todos", routers::todos::routes())
+        .nest(
+            "/todos",
+            routers::todos::routes(
+                todos_repository,
+                Arc::clone(&users_repository),
+                authentication.clone(),
+            ),
+        )
+        .nest(
+            "/users/me",
+            routers::profiles::routes(
+                Arc::clone(&users_repository),
+                Arc::clone(&audit_repository),
+                authentication.clone(),
+            ),
+        )
+        .nest(
+            "/users/me/password",
+            routers::passwords::me_routes(
+                Arc::clone(&users_repository),
+                Arc::clone(&audit_repository),
+                Arc::clone(&mailer),
+                Arc::clone(&password_policy),
+                authentication.clone(),
+                Arc::clone(&login_throttle),
+                Arc::clone(&config),
+            ),
+        )
         .nest(
             "/users",
-            routers::users::routes(Arc::clone(&user_app_state)),
+            routers::users::routes(
+                Arc::clone(&users_repository),
+                Arc::clone(&audit_repository),
+                Arc::clone(&mailer),
+                Arc::clone(&password_policy),
+                authentication.clone(),
+                Arc::clone(&login_throttle),
+                Arc::clone(&config),
+            ),
+        )
+        .nest(
+            "/admin",
+            routers::admin::routes(Arc::clone(&audit_repository), authentication.clone()),
         )
         .nest(
             "/authentication",
-            routers::authentication::routes(Arc::clone(&user_app_state)),
+            routers::authentication::routes(
+                Arc::clone(&users_repository),
+                Arc::clone(&audit_repository),
+                Arc::clone(&jwt_keys),
+                Arc::clone(&login_throttle),
+                Arc::clone(&config),
+            )
+            .merge(routers::passwords::routes(
+                users_repository,
+                audit_repository,
+                mailer,
+                password_policy,
+                login_throttle,
+                Arc::clone(&config),
+            )),
         )
+        .nest("/.well-known", routers::well_known::routes(jwt_keys))
         .route("/health-check", get(default_routers::health_check))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&config),
+            csrf_origin_check,
+        ))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&config),
+            client_ip,
+        ))
         .layer(
             CorsLayer::new()
-                .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
-                .allow_origin(Any),
+                .allow_methods([
+                    Method::GET,
+                    Method::POST,
+                    Method::PUT,
+                    Method::PATCH,
+                    Method::DELETE,
+                ])
+                .allow_headers([
+                    header::CONTENT_TYPE,
+                    header::AUTHORIZATION,
+                    HeaderName::from_static("x-csrf-token"),
+                ])
+                .allow_origin(allow_origin(&config)?),
         )
         .layer(TraceLayer::new_for_http());
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3001));
-    let listener = TcpListener::bind(addr).await?;
+    Ok(app)
+}
 
-    info!("Server is running on port {}", 3001);
+fn allow_origin(config: &Config) -> Result<AllowOrigin> {
+    if config.server.cors_allowed_origins.iter().any(|o| o == "*") {
+        return Ok(AllowOrigin::from(Any));
+    }
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    let origins = config
+        .server
+        .cors_allowed_origins
+        .iter()
+        .map(|o| HeaderValue::from_str(o))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
 
-    Ok(())
+    Ok(AllowOrigin::list(origins))
 }
 
 async fn shutdown_signal() {
@@ -1,39 +1,242 @@
+use std::{net::SocketAddr, sync::Arc};
+
 use axum::{
-    extract::Request,
-    http::{StatusCode, header},
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, StatusCode, header},
     middleware::Next,
     response::Response,
 };
+use axum_extra::{
+    TypedHeader,
+    extract::cookie::CookieJar,
+    headers::{Authorization, authorization::Bearer},
+};
+
+use crate::{
+    application::usecases::personal_access_tokens::{PersonalAccessTokensUseCase, TOKEN_PREFIX},
+    config::config_model::Config,
+    domain::{
+        entities::{personal_access_tokens::TokenScope, users::Role},
+        errors::DomainError,
+        repositories::users::UsersRepository,
+    },
+    infrastructure::{
+        jwt_authentication::{jwt_keys::JwtKeys, jwt_model::Claims},
+        token_hashing,
+    },
+};
+
+// Built once in `http_serve::app` and shared by every router that needs a signed-in user.
+pub type AuthenticationState<T> = (Arc<PersonalAccessTokensUseCase<T>>, Arc<JwtKeys>, Arc<T>);
+
+#[derive(Debug, Clone)]
+pub struct ClientIp(pub String);
 
-use crate::infrastructure::jwt_authentication;
+#[derive(Debug, Clone)]
+pub enum Credential {
+    Session,
+    PersonalAccessToken(Vec<TokenScope>),
+}
+
+// With `trust_forwarded_for`, the last X-Forwarded-For entry is used: it is the one
+// our own proxy appended, while earlier entries are whatever the client sent.
+pub async fn client_ip(
+    State(config): State<Arc<Config>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let forwarded_for = req
+        .headers()
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| config.server.trust_forwarded_for && !ip.is_empty());
+
+    let ip = forwarded_for
+        .or_else(|| {
+            req.extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
 
-pub async fn user_authentication(mut req: Request, next: Next) -> Result<Response, StatusCode> {
-    if let Some(cookie_header) = req.headers().get(header::COOKIE) {
-        if let Ok(cookie_str) = cookie_header.to_str() {
-            let access_token = get_cookie_value(cookie_str, "act");
+    req.extensions_mut().insert(ClientIp(ip));
 
-            if let Some(token) = access_token {
-                if let Ok(claims) = jwt_authentication::verify_toke("secret".to_string(), token) {
-                    req.extensions_mut().insert(claims.sub);
+    next.run(req).await
+}
+
+// Browsers attach the session cookies to cross-site requests, so unsafe requests that
+// rely on them must come from a trusted origin. Bearer requests are not affected.
+pub async fn csrf_origin_check(
+    State(config): State<Arc<Config>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if req.method().is_safe() || !uses_session_cookies(req.headers()) {
+        return Ok(next.run(req).await);
+    }
 
-                    return Ok(next.run(req).await);
-                }
+    let headers = req.headers();
+    let origin = match headers.get(header::ORIGIN) {
+        Some(origin) => origin.to_str().ok().map(|origin| origin.to_string()),
+        None => headers
+            .get(header::REFERER)
+            .and_then(|referer| referer.to_str().ok())
+            .and_then(origin_of),
+    };
+
+    match origin {
+        Some(origin) if config.csrf.trusted_origins.contains(&origin) => Ok(next.run(req).await),
+        _ => Err(StatusCode::FORBIDDEN),
+    }
+}
+
+pub async fn user_authentication<T>(
+    State((personal_access_tokens_use_case, jwt_keys, users_repository)): State<
+        AuthenticationState<T>,
+    >,
+    bearer: Option<TypedHeader<Authorization<Bearer>>>,
+    jar: CookieJar,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode>
+where
+    T: UsersRepository + Send + Sync,
+{
+    let access_token = match bearer {
+        Some(TypedHeader(Authorization(bearer))) => Some(bearer.token().to_string()),
+        None => match jar.get("act") {
+            Some(_) if !req.method().is_safe() && !csrf_token_matches(req.headers(), &jar) => {
+                return Err(StatusCode::FORBIDDEN);
             }
+            Some(cookie) => Some(cookie.value().to_string()),
+            None => None,
+        },
+    };
+
+    let Some(token) = access_token else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+
+    // Personal access tokens carry scopes instead of a role, so they never pass `require_role`.
+    if token.starts_with(TOKEN_PREFIX) {
+        let personal_access_token = match personal_access_tokens_use_case.authenticate(token).await
+        {
+            Ok(personal_access_token) => personal_access_token,
+            Err(DomainError::Unauthorized(_)) => return Err(StatusCode::UNAUTHORIZED),
+            Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        };
+
+        req.extensions_mut().insert(personal_access_token.user_id);
+        req.extensions_mut().insert(Credential::PersonalAccessToken(
+            personal_access_token.scopes,
+        ));
+
+        return Ok(next.run(req).await);
+    }
+
+    let Ok(claims) = jwt_keys.verify_token::<Claims>(&token) else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+
+    // The role in the token is the one at login. Reading the user again makes a role
+    // change apply to tokens that are already out.
+    let user = match users_repository.find_by_id(claims.sub).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return Err(StatusCode::UNAUTHORIZED),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    // Tokens issued before sessions were checked have no `sid`; refreshing gets one.
+    let Some(session_id) = claims.sid else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+    match users_repository.is_session_active(session_id).await {
+        Ok(true) => {}
+        Ok(false) => return Err(StatusCode::UNAUTHORIZED),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+
+    req.extensions_mut().insert(user.id);
+    req.extensions_mut().insert(user.role);
+    req.extensions_mut().insert(Credential::Session);
+
+    Ok(next.run(req).await)
+}
+
+// The guards below must run after `user_authentication`, which fills in the extensions they read.
+pub async fn require_role(
+    State(role): State<Role>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    match req.extensions().get::<Role>() {
+        Some(caller_role) if *caller_role == role => Ok(next.run(req).await),
+        _ => Err(StatusCode::FORBIDDEN),
+    }
+}
+
+pub async fn require_scope(
+    State(scope): State<TokenScope>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    match req.extensions().get::<Credential>() {
+        Some(Credential::Session) => Ok(next.run(req).await),
+        Some(Credential::PersonalAccessToken(scopes)) if scopes.contains(&scope) => {
+            Ok(next.run(req).await)
         }
+        _ => Err(StatusCode::FORBIDDEN),
     }
+}
 
-    Err(StatusCode::UNAUTHORIZED)
+pub async fn require_session(req: Request, next: Next) -> Result<Response, StatusCode> {
+    match req.extensions().get::<Credential>() {
+        Some(Credential::Session) => Ok(next.run(req).await),
+        _ => Err(StatusCode::FORBIDDEN),
+    }
 }
 
-fn get_cookie_value(cookie_header: &str, key: &str) -> Option<String> {
-    cookie_header.split("; ").find_map(|cookie| {
-        let mut parts = cookie.splitn(2, '=');
-        let name = parts.next()?.trim();
-        let value = parts.next()?.trim();
-        if name == key {
-            Some(value.to_string())
-        } else {
-            None
+// For the routes that read the `rft` cookie directly instead of going through `user_authentication`.
+pub async fn require_csrf(
+    jar: CookieJar,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if jar.get("rft").is_some() && !csrf_token_matches(req.headers(), &jar) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(next.run(req).await)
+}
+
+fn uses_session_cookies(headers: &HeaderMap) -> bool {
+    let jar = CookieJar::from_headers(headers);
+
+    !headers.contains_key(header::AUTHORIZATION)
+        && (jar.get("act").is_some() || jar.get("rft").is_some())
+}
+
+// Double-submit check: a cross-site page can make the browser send the `csrf` cookie,
+// but it cannot read it to copy it into the header.
+fn csrf_token_matches(headers: &HeaderMap, jar: &CookieJar) -> bool {
+    let header_token = headers
+        .get("x-csrf-token")
+        .and_then(|value| value.to_str().ok());
+
+    match (header_token, jar.get("csrf")) {
+        (Some(header_token), Some(cookie)) if !header_token.is_empty() => {
+            token_hashing::constant_time_eq(header_token.as_bytes(), cookie.value().as_bytes())
         }
-    })
+        _ => false,
+    }
+}
+
+// `https://example.com:8080/path?q` -> `https://example.com:8080`
+fn origin_of(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+
+    (!scheme.is_empty() && !authority.is_empty()).then(|| format!("{}://{}", scheme, authority))
 }
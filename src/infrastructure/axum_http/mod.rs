@@ -1,4 +1,6 @@
 pub mod default_routers;
+pub mod error_response;
+pub mod extractors;
 pub mod http_serve;
 pub mod middleware;
 pub mod routers;
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router, extract::State, http::StatusCode, middleware, response::IntoResponse,
+    routing::get,
+};
+use serde_json::json;
+
+use crate::{
+    application::usecases::audit::AuditUseCase,
+    domain::{
+        entities::users::Role,
+        repositories::{audit::AuditRepository, users::UsersRepository},
+        value_objects::audit_events::ListAuditEventsModel,
+    },
+    infrastructure::axum_http::{
+        extractors::Query,
+        middleware::{AuthenticationState, require_role, user_authentication},
+    },
+};
+
+pub fn routes<T, A>(audit_repository: Arc<A>, authentication: AuthenticationState<T>) -> Router
+where
+    T: UsersRepository + Send + Sync + 'static,
+    A: AuditRepository + Send + Sync + 'static,
+{
+    let audit_use_case = Arc::new(AuditUseCase::new(audit_repository));
+
+    Router::new()
+        .route("/audit", get(list_audit_events))
+        .route_layer(middleware::from_fn_with_state(Role::Admin, require_role))
+        .route_layer(middleware::from_fn_with_state(
+            authentication,
+            user_authentication::<T>,
+        ))
+        .with_state(audit_use_case)
+}
+
+pub async fn list_audit_events<A>(
+    State(audit_use_case): State<Arc<AuditUseCase<A>>>,
+    Query(list_audit_events_model): Query<ListAuditEventsModel>,
+) -> impl IntoResponse
+where
+    A: AuditRepository + Send + Sync,
+{
+    match audit_use_case.list(list_audit_events_model).await {
+        Ok(events) => (StatusCode::OK, Json(json!({"data": events}))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
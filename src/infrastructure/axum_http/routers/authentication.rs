@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use axum::{
-    Json, Router,
-    extract::State,
+    Extension, Router,
+    extract::{Path, State},
     http::{HeaderMap, HeaderValue, StatusCode, header},
-    response::IntoResponse,
-    routing::post,
+    middleware,
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
 };
 use axum_extra::extract::cookie::{Cookie, CookieJar};
 use cookie::time::Duration;
@@ -13,120 +14,392 @@ use serde_json::json;
 
 use crate::{
     application::usecases::authentication::AuthenticationUseCase,
-    domain::repositories::users::UsersRepository,
+    config::config_model::Config,
+    domain::{
+        errors::DomainError,
+        repositories::{audit::AuditRepository, users::UsersRepository},
+    },
     infrastructure::{
-        app_state::repositories::users::UsersAppState,
-        jwt_authentication::authentication_model::LoginModel,
+        axum_http::{
+            extractors::{Json, Query},
+            middleware::{ClientIp, require_csrf},
+        },
+        jwt_authentication::{
+            authentication_model::{
+                DeviceModel, LoginModel, LoginOptionsModel, LoginOutcome, LoginResponseMode,
+                OidcCallbackModel, RefreshTokenModel, TotpLoginModel,
+            },
+            jwt_keys::JwtKeys,
+            jwt_model::Passport,
+        },
+        login_throttle::LoginThrottle,
+        token_hashing,
     },
 };
 
-pub fn routes(users_state: Arc<UsersAppState>) -> Router {
-    let authentication_use_case = Arc::new(AuthenticationUseCase::new(users_state));
+// Lax rather than Strict: the callback is a cross-site redirect from the provider.
+const OIDC_STATE_COOKIE: &str = "oidc_state";
+
+type RouterState<T, A> = (Arc<AuthenticationUseCase<T, A>>, Arc<Config>);
+
+pub fn routes<T, A>(
+    users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+    jwt_keys: Arc<JwtKeys>,
+    login_throttle: Arc<LoginThrottle>,
+    config: Arc<Config>,
+) -> Router
+where
+    T: UsersRepository + Send + Sync + 'static,
+    A: AuditRepository + Send + Sync + 'static,
+{
+    let authentication_use_case = Arc::new(AuthenticationUseCase::new(
+        users_repository,
+        audit_repository,
+        jwt_keys,
+        login_throttle,
+        Arc::clone(&config),
+    ));
+
+    let session_routes = Router::new()
+        .route("/refresh-token", post(refresh_token))
+        .route("/logout", post(logout))
+        .route("/logout-all", post(logout_all))
+        .route_layer(middleware::from_fn(require_csrf));
 
     Router::new()
         .route("/login", post(login))
-        .route("/refresh-token", post(refresh_token))
-        .with_state(authentication_use_case)
+        .route("/login/totp", post(login_totp))
+        .route("/oidc/{provider}/login", get(oidc_login))
+        .route("/oidc/{provider}/callback", get(oidc_callback))
+        .merge(session_routes)
+        .with_state((authentication_use_case, config))
 }
 
-pub async fn login<T>(
-    State(authentication_use_case): State<Arc<AuthenticationUseCase<T>>>,
+pub async fn login<T, A>(
+    State((authentication_use_case, config)): State<RouterState<T, A>>,
+    Query(login_options): Query<LoginOptionsModel>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    headers: HeaderMap,
     Json(login_model): Json<LoginModel>,
 ) -> impl IntoResponse
 where
     T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
 {
-    match authentication_use_case.login(login_model).await {
-        Ok(passport) => {
-            let act_cookie = Cookie::build(("act", passport.access_token.clone()))
-                .path("/")
-                .same_site(cookie::SameSite::Lax)
-                .http_only(true)
-                .max_age(Duration::days(14));
+    match authentication_use_case
+        .login(login_model, device(client_ip, &headers))
+        .await
+    {
+        Ok(LoginOutcome::Passport(passport)) => {
+            passport_response(passport, &login_options, &headers, &config)
+        }
+        Ok(LoginOutcome::TotpRequired(challenge)) => {
+            (StatusCode::OK, Json(json!({ "data": challenge }))).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
 
-            let rft_cookie = Cookie::build(("rft", passport.refresh_token.clone()))
-                .path("/")
+pub async fn login_totp<T, A>(
+    State((authentication_use_case, config)): State<RouterState<T, A>>,
+    Query(login_options): Query<LoginOptionsModel>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    headers: HeaderMap,
+    Json(totp_login_model): Json<TotpLoginModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match authentication_use_case
+        .login_totp(totp_login_model, device(client_ip, &headers))
+        .await
+    {
+        Ok(passport) => passport_response(passport, &login_options, &headers, &config),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn oidc_login<T, A>(
+    State((authentication_use_case, config)): State<RouterState<T, A>>,
+    Path(provider): Path<String>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match authentication_use_case.start_oidc_login(provider).await {
+        Ok(authorization) => {
+            let state_cookie = Cookie::build((OIDC_STATE_COOKIE, authorization.state_token))
+                .path("/authentication/oidc")
                 .same_site(cookie::SameSite::Lax)
                 .http_only(true)
-                .max_age(Duration::days(14));
+                .max_age(Duration::seconds(config.oidc.state_ttl_secs));
 
             let mut headers = HeaderMap::new();
             headers.append(
                 header::SET_COOKIE,
-                HeaderValue::from_str(&act_cookie.to_string()).unwrap(),
-            );
-            headers.append(
-                header::SET_COOKIE,
-                HeaderValue::from_str(&rft_cookie.to_string()).unwrap(),
+                HeaderValue::from_str(&state_cookie.to_string()).unwrap(),
             );
 
-            (
-                StatusCode::OK,
-                headers,
-                Json(json!({ "message": "Login Successfully" })),
-            )
-                .into_response()
+            (headers, Redirect::to(&authorization.authorization_url)).into_response()
         }
-        Err(_) => (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Unauthorized" })),
-        )
-            .into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
-pub async fn refresh_token<T>(
-    State(authentication_use_case): State<Arc<AuthenticationUseCase<T>>>,
+pub async fn oidc_callback<T, A>(
+    State((authentication_use_case, config)): State<RouterState<T, A>>,
+    Path(provider): Path<String>,
+    Query(callback): Query<OidcCallbackModel>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    headers: HeaderMap,
     jar: CookieJar,
 ) -> impl IntoResponse
 where
     T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
 {
-    if let Some(rft) = jar.get("rft") {
-        let refresh_token = rft.value().to_string();
-
-        let response = match authentication_use_case.refresh_token(refresh_token).await {
-            Ok(passport) => {
-                let act_cookie = Cookie::build(("act", passport.access_token.clone()))
-                    .path("/")
-                    .same_site(cookie::SameSite::Lax)
-                    .http_only(true)
-                    .max_age(Duration::days(14));
-
-                let rft_cookie = Cookie::build(("rft", passport.refresh_token.clone()))
-                    .path("/")
-                    .same_site(cookie::SameSite::Lax)
-                    .http_only(true)
-                    .max_age(Duration::days(14));
-
-                let mut headers = HeaderMap::new();
-                headers.append(
-                    header::SET_COOKIE,
-                    HeaderValue::from_str(&act_cookie.to_string()).unwrap(),
-                );
-                headers.append(
-                    header::SET_COOKIE,
-                    HeaderValue::from_str(&rft_cookie.to_string()).unwrap(),
-                );
-
-                (
+    let state_token = jar
+        .get(OIDC_STATE_COOKIE)
+        .map(|cookie| cookie.value().to_string());
+
+    match authentication_use_case
+        .login_oidc(provider, callback, state_token, device(client_ip, &headers))
+        .await
+    {
+        Ok(LoginOutcome::Passport(passport)) => {
+            let mut headers = passport_cookies(&passport, &token_hashing::generate(""), &config);
+            headers.append(header::SET_COOKIE, clear_oidc_state_cookie());
+
+            match &config.oidc.login_redirect_url {
+                Some(url) => (headers, Redirect::to(url)).into_response(),
+                None => (
                     StatusCode::OK,
-                    Json(json!({ "message": "Refresh token successfully" })),
+                    headers,
+                    Json(json!({ "message": "Login Successfully" })),
                 )
-                    .into_response()
+                    .into_response(),
             }
-            Err(_) => (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({ "error": "Unauthorized" })),
-            )
-                .into_response(),
-        };
-        return response;
+        }
+        Ok(LoginOutcome::TotpRequired(challenge)) => (
+            StatusCode::OK,
+            [(header::SET_COOKIE, clear_oidc_state_cookie())],
+            Json(json!({ "data": challenge })),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn refresh_token<T, A>(
+    State((authentication_use_case, config)): State<RouterState<T, A>>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    headers: HeaderMap,
+    jar: CookieJar,
+    body: Option<Json<RefreshTokenModel>>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Some(refresh_token) = presented_refresh_token(&jar, body) else {
+        return DomainError::validation("Missing refresh token").into_response();
+    };
+
+    let passport = match authentication_use_case
+        .refresh_token(refresh_token, device(client_ip, &headers))
+        .await
+    {
+        Ok(passport) => passport,
+        Err(e) => return e.into_response(),
+    };
+
+    // Answer the way the token came in: cookies for browsers, the body for everyone else.
+    if jar.get("rft").is_none() {
+        return (StatusCode::OK, Json(json!({ "data": passport }))).into_response();
+    }
+
+    let csrf_token = jar
+        .get("csrf")
+        .map(|csrf| csrf.value().to_string())
+        .unwrap_or_else(|| token_hashing::generate(""));
+
+    (
+        StatusCode::OK,
+        passport_cookies(&passport, &csrf_token, &config),
+        Json(json!({ "message": "Refresh token successfully" })),
+    )
+        .into_response()
+}
+
+pub async fn logout<T, A>(
+    State((authentication_use_case, _)): State<RouterState<T, A>>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    jar: CookieJar,
+    body: Option<Json<RefreshTokenModel>>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    if let Some(refresh_token) = presented_refresh_token(&jar, body) {
+        match authentication_use_case
+            .logout(refresh_token, client_ip)
+            .await
+        {
+            // An unknown or already revoked token still gets its cookies cleared.
+            Ok(_) | Err(DomainError::Unauthorized(_)) => {}
+            Err(e) => return e.into_response(),
+        }
+    }
+
+    (
+        StatusCode::OK,
+        clear_passport_cookies(),
+        Json(json!({ "message": "Logout successfully" })),
+    )
+        .into_response()
+}
+
+pub async fn logout_all<T, A>(
+    State((authentication_use_case, _)): State<RouterState<T, A>>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    jar: CookieJar,
+    body: Option<Json<RefreshTokenModel>>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Some(refresh_token) = presented_refresh_token(&jar, body) else {
+        return DomainError::validation("Missing refresh token").into_response();
     };
 
+    match authentication_use_case
+        .logout_all(refresh_token, client_ip)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            clear_passport_cookies(),
+            Json(json!({ "message": "Logout from all devices successfully" })),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+// The `rft` cookie wins; clients without cookies send `{ "refresh_token": ... }` instead.
+fn presented_refresh_token(
+    jar: &CookieJar,
+    body: Option<Json<RefreshTokenModel>>,
+) -> Option<String> {
+    jar.get("rft")
+        .map(|rft| rft.value().to_string())
+        .or_else(|| body.map(|Json(body)| body.refresh_token))
+}
+
+fn passport_response(
+    passport: Passport,
+    login_options: &LoginOptionsModel,
+    headers: &HeaderMap,
+    config: &Config,
+) -> Response {
+    if login_options.response == LoginResponseMode::Json || accepts_only_json(headers) {
+        return (StatusCode::OK, Json(json!({ "data": passport }))).into_response();
+    }
+
     (
-        StatusCode::BAD_REQUEST,
-        Json(json!({"error": "Bad Request"})),
+        StatusCode::OK,
+        passport_cookies(&passport, &token_hashing::generate(""), config),
+        Json(json!({ "message": "Login Successfully" })),
     )
         .into_response()
 }
+
+// The state is spent once the provider has called back, whatever comes next.
+fn clear_oidc_state_cookie() -> HeaderValue {
+    let state_cookie = Cookie::build((OIDC_STATE_COOKIE, ""))
+        .path("/authentication/oidc")
+        .http_only(true)
+        .max_age(Duration::ZERO);
+
+    HeaderValue::from_str(&state_cookie.to_string()).unwrap()
+}
+
+fn device(client_ip: String, headers: &HeaderMap) -> DeviceModel {
+    DeviceModel {
+        ip_address: client_ip,
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|user_agent| user_agent.to_str().ok())
+            .map(|user_agent| user_agent.chars().take(256).collect()),
+    }
+}
+
+// Browsers and most HTTP libraries send `Accept` lists such as `application/json, */*`,
+// so only a bare `application/json` opts out of cookies.
+fn accepts_only_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.trim().eq_ignore_ascii_case("application/json"))
+}
+
+fn passport_cookies(passport: &Passport, csrf_token: &str, config: &Config) -> HeaderMap {
+    let act_cookie = Cookie::build(("act", passport.access_token.clone()))
+        .path("/")
+        .same_site(cookie::SameSite::Lax)
+        .http_only(true)
+        .max_age(Duration::seconds(config.jwt.cookie_max_age_secs));
+
+    let rft_cookie = Cookie::build(("rft", passport.refresh_token.clone()))
+        .path("/")
+        .same_site(cookie::SameSite::Lax)
+        .http_only(true)
+        .max_age(Duration::seconds(config.jwt.cookie_max_age_secs));
+
+    // Readable by scripts on purpose: clients echo it back in the `X-CSRF-Token` header.
+    let csrf_cookie = Cookie::build(("csrf", csrf_token.to_string()))
+        .path("/")
+        .same_site(cookie::SameSite::Lax)
+        .max_age(Duration::seconds(config.jwt.cookie_max_age_secs));
+
+    let mut headers = HeaderMap::new();
+    headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&act_cookie.to_string()).unwrap(),
+    );
+    headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&rft_cookie.to_string()).unwrap(),
+    );
+    headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&csrf_cookie.to_string()).unwrap(),
+    );
+
+    headers
+}
+
+pub(crate) fn clear_passport_cookies() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    for name in ["act", "rft", "csrf"] {
+        let cookie = Cookie::build((name, ""))
+            .path("/")
+            .same_site(cookie::SameSite::Lax)
+            .http_only(name != "csrf")
+            .max_age(Duration::ZERO)
+            .expires(cookie::time::OffsetDateTime::UNIX_EPOCH);
+
+        headers.append(
+            header::SET_COOKIE,
+            HeaderValue::from_str(&cookie.to_string()).unwrap(),
+        );
+    }
+
+    headers
+}
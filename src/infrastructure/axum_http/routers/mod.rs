@@ -1,3 +1,10 @@
+pub mod admin;
 pub mod authentication;
+pub mod passwords;
+pub mod personal_access_tokens;
+pub mod profiles;
+pub mod sessions;
 pub mod todos;
+pub mod totp;
 pub mod users;
+pub mod well_known;
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Router, extract::State, http::StatusCode, middleware, response::IntoResponse,
+    routing::post,
+};
+use axum_extra::extract::cookie::CookieJar;
+use serde_json::json;
+
+use crate::{
+    application::usecases::passwords::PasswordsUseCase,
+    config::config_model::Config,
+    domain::{
+        mailer::Mailer,
+        repositories::{audit::AuditRepository, users::UsersRepository},
+        value_objects::users::{
+            ChangePasswordModel, ForgotPasswordModel, PasswordPolicyModel, ResetPasswordModel,
+        },
+    },
+    infrastructure::{
+        axum_http::{
+            extractors::Json,
+            middleware::{AuthenticationState, ClientIp, require_session, user_authentication},
+        },
+        login_throttle::LoginThrottle,
+    },
+};
+
+pub fn routes<T, M, A>(
+    users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+    mailer: Arc<M>,
+    password_policy: Arc<PasswordPolicyModel>,
+    login_throttle: Arc<LoginThrottle>,
+    config: Arc<Config>,
+) -> Router
+where
+    T: UsersRepository + Send + Sync + 'static,
+    M: Mailer + Send + Sync + 'static,
+    A: AuditRepository + Send + Sync + 'static,
+{
+    let passwords_use_case = Arc::new(PasswordsUseCase::new(
+        users_repository,
+        audit_repository,
+        mailer,
+        password_policy,
+        login_throttle,
+        config,
+    ));
+
+    Router::new()
+        .route("/forgot-password", post(forgot_password))
+        .route("/reset-password", post(reset_password))
+        .with_state(passwords_use_case)
+}
+
+pub fn me_routes<T, M, A>(
+    users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+    mailer: Arc<M>,
+    password_policy: Arc<PasswordPolicyModel>,
+    authentication: AuthenticationState<T>,
+    login_throttle: Arc<LoginThrottle>,
+    config: Arc<Config>,
+) -> Router
+where
+    T: UsersRepository + Send + Sync + 'static,
+    M: Mailer + Send + Sync + 'static,
+    A: AuditRepository + Send + Sync + 'static,
+{
+    let passwords_use_case = Arc::new(PasswordsUseCase::new(
+        users_repository,
+        audit_repository,
+        mailer,
+        password_policy,
+        login_throttle,
+        config,
+    ));
+
+    Router::new()
+        .route("/", post(change_password))
+        .route_layer(middleware::from_fn(require_session))
+        .route_layer(middleware::from_fn_with_state(
+            authentication,
+            user_authentication::<T>,
+        ))
+        .with_state(passwords_use_case)
+}
+
+pub async fn change_password<T, M, A>(
+    State(passwords_use_case): State<Arc<PasswordsUseCase<T, M, A>>>,
+    Extension(user_id): Extension<String>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    jar: CookieJar,
+    Json(change_password_model): Json<ChangePasswordModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let refresh_token = jar.get("rft").map(|rft| rft.value().to_string());
+
+    match passwords_use_case
+        .change_password(user_id, change_password_model, refresh_token, client_ip)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Change password success"})),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn forgot_password<T, M, A>(
+    State(passwords_use_case): State<Arc<PasswordsUseCase<T, M, A>>>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    Json(forgot_password_model): Json<ForgotPasswordModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match passwords_use_case
+        .forgot_password(forgot_password_model, &client_ip)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::ACCEPTED,
+            Json(json!({"message": "If the account exists, a reset link has been sent"})),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn reset_password<T, M, A>(
+    State(passwords_use_case): State<Arc<PasswordsUseCase<T, M, A>>>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    Json(reset_password_model): Json<ResetPasswordModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match passwords_use_case
+        .reset_password(reset_password_model, client_ip)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Reset password success"})),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
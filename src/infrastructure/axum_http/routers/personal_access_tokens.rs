@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::IntoResponse,
+    routing::{delete, get, post},
+};
+use serde_json::json;
+
+use crate::{
+    application::usecases::personal_access_tokens::PersonalAccessTokensUseCase,
+    domain::{
+        repositories::users::UsersRepository,
+        value_objects::personal_access_tokens::CreatePersonalAccessTokenModel,
+    },
+    infrastructure::axum_http::{
+        extractors::Json,
+        middleware::{AuthenticationState, require_session, user_authentication},
+    },
+};
+
+// Managing tokens needs a real login, so a leaked token cannot mint more tokens.
+pub fn routes<T>(authentication: AuthenticationState<T>) -> Router
+where
+    T: UsersRepository + Send + Sync + 'static,
+{
+    let personal_access_tokens_use_case = Arc::clone(&authentication.0);
+
+    Router::new()
+        .route("/", post(create))
+        .route("/", get(list))
+        .route("/{id}", delete(revoke))
+        .route_layer(middleware::from_fn(require_session))
+        .route_layer(middleware::from_fn_with_state(
+            authentication,
+            user_authentication::<T>,
+        ))
+        .with_state(personal_access_tokens_use_case)
+}
+
+pub async fn create<T>(
+    State(personal_access_tokens_use_case): State<Arc<PersonalAccessTokensUseCase<T>>>,
+    Extension(user_id): Extension<String>,
+    Json(create_model): Json<CreatePersonalAccessTokenModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+{
+    match personal_access_tokens_use_case
+        .create(user_id, create_model)
+        .await
+    {
+        Ok(token) => (StatusCode::CREATED, Json(json!({"data": token}))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn list<T>(
+    State(personal_access_tokens_use_case): State<Arc<PersonalAccessTokensUseCase<T>>>,
+    Extension(user_id): Extension<String>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+{
+    match personal_access_tokens_use_case.list(user_id).await {
+        Ok(tokens) => (StatusCode::OK, Json(json!({"data": tokens}))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn revoke<T>(
+    State(personal_access_tokens_use_case): State<Arc<PersonalAccessTokensUseCase<T>>>,
+    Extension(user_id): Extension<String>,
+    Path(id): Path<String>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+{
+    match personal_access_tokens_use_case.revoke(user_id, id).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Revoke token success"})),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Router, extract::State, http::StatusCode, middleware, response::IntoResponse,
+    routing::get,
+};
+use serde_json::json;
+
+use crate::{
+    application::usecases::profiles::ProfilesUseCase,
+    domain::{
+        repositories::{audit::AuditRepository, users::UsersRepository},
+        value_objects::users::UpdateProfileModel,
+    },
+    infrastructure::axum_http::{
+        extractors::Json,
+        middleware::{AuthenticationState, ClientIp, require_session, user_authentication},
+        routers::authentication::clear_passport_cookies,
+    },
+};
+
+pub fn routes<T, A>(
+    users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+    authentication: AuthenticationState<T>,
+) -> Router
+where
+    T: UsersRepository + Send + Sync + 'static,
+    A: AuditRepository + Send + Sync + 'static,
+{
+    let profiles_use_case = Arc::new(ProfilesUseCase::new(users_repository, audit_repository));
+
+    Router::new()
+        .route(
+            "/",
+            get(get_profile)
+                .patch(update_profile)
+                .delete(delete_profile),
+        )
+        .route_layer(middleware::from_fn(require_session))
+        .route_layer(middleware::from_fn_with_state(
+            authentication,
+            user_authentication::<T>,
+        ))
+        .with_state(profiles_use_case)
+}
+
+pub async fn get_profile<T, A>(
+    State(profiles_use_case): State<Arc<ProfilesUseCase<T, A>>>,
+    Extension(user_id): Extension<String>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match profiles_use_case.get(user_id).await {
+        Ok(user) => (StatusCode::OK, Json(json!({"data": user}))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn update_profile<T, A>(
+    State(profiles_use_case): State<Arc<ProfilesUseCase<T, A>>>,
+    Extension(user_id): Extension<String>,
+    Json(profile_model): Json<UpdateProfileModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match profiles_use_case.update(user_id, profile_model).await {
+        Ok(user) => (StatusCode::OK, Json(json!({"data": user}))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn delete_profile<T, A>(
+    State(profiles_use_case): State<Arc<ProfilesUseCase<T, A>>>,
+    Extension(user_id): Extension<String>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match profiles_use_case.delete(user_id, client_ip).await {
+        Ok(_) => (
+            StatusCode::OK,
+            clear_passport_cookies(),
+            Json(json!({"message": "Delete account success"})),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    response::IntoResponse,
+    routing::{delete, get},
+};
+use serde_json::json;
+
+use crate::{
+    application::usecases::sessions::SessionsUseCase,
+    domain::repositories::{audit::AuditRepository, users::UsersRepository},
+    infrastructure::axum_http::middleware::{
+        AuthenticationState, ClientIp, require_session, user_authentication,
+    },
+};
+
+pub fn routes<T, A>(
+    users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+    authentication: AuthenticationState<T>,
+) -> Router
+where
+    T: UsersRepository + Send + Sync + 'static,
+    A: AuditRepository + Send + Sync + 'static,
+{
+    let sessions_use_case = Arc::new(SessionsUseCase::new(users_repository, audit_repository));
+
+    Router::new()
+        .route("/", get(list))
+        .route("/{id}", delete(revoke))
+        .route_layer(middleware::from_fn(require_session))
+        .route_layer(middleware::from_fn_with_state(
+            authentication,
+            user_authentication::<T>,
+        ))
+        .with_state(sessions_use_case)
+}
+
+pub async fn list<T, A>(
+    State(sessions_use_case): State<Arc<SessionsUseCase<T, A>>>,
+    Extension(user_id): Extension<String>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match sessions_use_case.list(user_id).await {
+        Ok(sessions) => (StatusCode::OK, Json(json!({"data": sessions}))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn revoke<T, A>(
+    State(sessions_use_case): State<Arc<SessionsUseCase<T, A>>>,
+    Extension(user_id): Extension<String>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    Path(id): Path<String>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match sessions_use_case.revoke(user_id, id, client_ip).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Revoke session success"})),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -1,175 +1,199 @@
 use std::sync::Arc;
 
 use axum::{
-    Extension, Json, Router,
+    Extension, Router,
     extract::{Path, State},
     http::StatusCode,
     middleware,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::{delete, get, patch, post},
 };
 use serde_json::json;
-use validator::Validate;
 
 use crate::{
     application::usecases::todos::TodosUseCase,
     domain::{
-        repositories::todos::TodosRepository,
-        value_objects::todos::{AddTodoModel, TodoErrorMessage},
+        entities::personal_access_tokens::TokenScope,
+        repositories::{todos::TodosRepository, users::UsersRepository},
+        value_objects::todos::{
+            AddTodoModel, ListTodosModel, TodoPageModel, TodoViewModel, UpdateTodoModel,
+        },
     },
-    infrastructure::{
-        app_state::repositories::todos::TodosAppState, axum_http::middleware::user_authentication,
+    infrastructure::axum_http::{
+        extractors::{Json, Query},
+        middleware::{AuthenticationState, require_scope, user_authentication},
     },
 };
 
-pub fn routes() -> Router {
-    let app_state = Arc::new(TodosAppState::new());
-    let todos_use_case = Arc::new(TodosUseCase::new(app_state));
+pub fn routes<T, U>(
+    todos_repository: Arc<T>,
+    users_repository: Arc<U>,
+    authentication: AuthenticationState<U>,
+) -> Router
+where
+    T: TodosRepository + Send + Sync + 'static,
+    U: UsersRepository + Send + Sync + 'static,
+{
+    let todos_use_case = Arc::new(TodosUseCase::new(todos_repository, users_repository));
 
-    Router::new()
-        .route("/", post(add_todo))
+    let read_routes = Router::new()
         .route("/", get(list))
+        .route("/today", get(today))
+        .route("/upcoming", get(upcoming))
         .route("/{id}", get(get_todo))
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::TodosRead,
+            require_scope,
+        ));
+
+    let write_routes = Router::new()
+        .route("/", post(add_todo))
+        .route("/{id}", patch(update_todo))
         .route("/to_completed/{id}", patch(to_completed))
         .route("/{id}", delete(delete_todo))
-        .route_layer(middleware::from_fn(user_authentication))
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::TodosWrite,
+            require_scope,
+        ));
+
+    Router::new()
+        .merge(read_routes)
+        .merge(write_routes)
+        .route_layer(middleware::from_fn_with_state(
+            authentication,
+            user_authentication::<U>,
+        ))
         .with_state(todos_use_case)
 }
 
-pub async fn add_todo<T>(
-    State(todos_use_case): State<Arc<TodosUseCase<T>>>,
+pub async fn add_todo<T, U>(
+    State(todos_use_case): State<Arc<TodosUseCase<T, U>>>,
     Extension(user_id): Extension<String>,
     Json(add_todo_model): Json<AddTodoModel>,
 ) -> impl IntoResponse
 where
     T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
 {
-    match add_todo_model.validate() {
-        Ok(_) => match todos_use_case.add(user_id, add_todo_model).await {
-            Ok(todo) => (StatusCode::CREATED, Json(json!({"data": todo}))).into_response(),
-            Err(e) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": e.to_string()
-                })),
-            )
-                .into_response(),
-        },
-        Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": e.to_string()})),
-            )
-                .into_response();
-        }
+    match todos_use_case.add(user_id, add_todo_model).await {
+        Ok(todo) => (StatusCode::CREATED, Json(json!({"data": todo}))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn list<T, U>(
+    State(todos_use_case): State<Arc<TodosUseCase<T, U>>>,
+    Extension(user_id): Extension<String>,
+    Query(list_todos_model): Query<ListTodosModel>,
+) -> impl IntoResponse
+where
+    T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
+{
+    match todos_use_case.list(user_id, list_todos_model).await {
+        Ok(page) => page_response(page),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn today<T, U>(
+    State(todos_use_case): State<Arc<TodosUseCase<T, U>>>,
+    Extension(user_id): Extension<String>,
+    Query(todo_view_model): Query<TodoViewModel>,
+) -> impl IntoResponse
+where
+    T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
+{
+    match todos_use_case.today(user_id, todo_view_model).await {
+        Ok(page) => page_response(page),
+        Err(e) => e.into_response(),
     }
 }
 
-pub async fn list<T>(State(todos_use_case): State<Arc<TodosUseCase<T>>>) -> impl IntoResponse
+pub async fn upcoming<T, U>(
+    State(todos_use_case): State<Arc<TodosUseCase<T, U>>>,
+    Extension(user_id): Extension<String>,
+    Query(todo_view_model): Query<TodoViewModel>,
+) -> impl IntoResponse
 where
     T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
 {
-    match todos_use_case.list().await {
-        Ok(todos) => (
-            StatusCode::OK,
-            Json(json!({
-                "data": todos,
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
+    match todos_use_case.upcoming(user_id, todo_view_model).await {
+        Ok(page) => page_response(page),
+        Err(e) => e.into_response(),
     }
 }
 
-pub async fn get_todo<T>(
-    State(todos_use_case): State<Arc<TodosUseCase<T>>>,
+pub async fn get_todo<T, U>(
+    State(todos_use_case): State<Arc<TodosUseCase<T, U>>>,
+    Extension(user_id): Extension<String>,
     Path(id): Path<String>,
 ) -> impl IntoResponse
 where
     T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
 {
-    match todos_use_case.get(id).await {
+    match todos_use_case.get(user_id, id).await {
         Ok(todo) => (StatusCode::OK, Json(json!({"data": todo}))).into_response(),
-        Err(e) => {
-            let error_message = e.to_string();
-
-            if error_message.contains(&TodoErrorMessage::NotFound.to_string()) {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(json!({"error": "Todo not found"})),
-                )
-                    .into_response()
-            } else {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Internal Server Error"})),
-                )
-                    .into_response()
-            }
-        }
+        Err(e) => e.into_response(),
     }
 }
 
-pub async fn to_completed<T>(
-    State(todos_use_case): State<Arc<TodosUseCase<T>>>,
+pub async fn update_todo<T, U>(
+    State(todos_use_case): State<Arc<TodosUseCase<T, U>>>,
+    Extension(user_id): Extension<String>,
     Path(id): Path<String>,
+    Json(update_todo_model): Json<UpdateTodoModel>,
 ) -> impl IntoResponse
 where
     T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
 {
-    match todos_use_case.to_completed(id).await {
+    match todos_use_case.update(user_id, id, update_todo_model).await {
         Ok(todo) => (StatusCode::OK, Json(json!({"data": todo}))).into_response(),
-        Err(e) => {
-            let error_message = e.to_string();
+        Err(e) => e.into_response(),
+    }
+}
 
-            if error_message.contains(&TodoErrorMessage::NotFound.to_string()) {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(json!({"error": "Todo not found"})),
-                )
-                    .into_response()
-            } else {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Internal Server Error"})),
-                )
-                    .into_response()
-            }
-        }
+pub async fn to_completed<T, U>(
+    State(todos_use_case): State<Arc<TodosUseCase<T, U>>>,
+    Extension(user_id): Extension<String>,
+    Path(id): Path<String>,
+) -> impl IntoResponse
+where
+    T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
+{
+    match todos_use_case.to_completed(user_id, id).await {
+        Ok(todo) => (StatusCode::OK, Json(json!({"data": todo}))).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
-pub async fn delete_todo<T>(
-    State(todos_use_case): State<Arc<TodosUseCase<T>>>,
+pub async fn delete_todo<T, U>(
+    State(todos_use_case): State<Arc<TodosUseCase<T, U>>>,
+    Extension(user_id): Extension<String>,
     Path(id): Path<String>,
 ) -> impl IntoResponse
 where
     T: TodosRepository + Send + Sync,
+    U: UsersRepository + Send + Sync,
 {
-    match todos_use_case.delete(id).await {
+    match todos_use_case.delete(user_id, id).await {
         Ok(_) => (StatusCode::OK, Json(json!({"message": "Success" }))).into_response(),
-        Err(e) => {
-            let error_message = e.to_string();
-
-            if error_message.contains(&TodoErrorMessage::NotFound.to_string()) {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(json!({"error": "Todo not found"})),
-                )
-                    .into_response()
-            } else {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Internal Server Error"})),
-                )
-                    .into_response()
-            }
-        }
+        Err(e) => e.into_response(),
     }
 }
+
+fn page_response(page: TodoPageModel) -> Response {
+    (
+        StatusCode::OK,
+        Json(json!({
+            "data": page.data,
+            "next_cursor": page.next_cursor,
+        })),
+    )
+        .into_response()
+}
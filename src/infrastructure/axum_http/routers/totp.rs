@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Router, extract::State, http::StatusCode, middleware, response::IntoResponse,
+    routing::post,
+};
+use serde_json::json;
+
+use crate::{
+    application::usecases::totp::TotpUseCase,
+    config::config_model::Config,
+    domain::{
+        repositories::users::UsersRepository,
+        value_objects::totp::{TotpCodeModel, TotpStepUpModel},
+    },
+    infrastructure::{
+        axum_http::{
+            extractors::Json,
+            middleware::{AuthenticationState, ClientIp, require_session, user_authentication},
+        },
+        login_throttle::LoginThrottle,
+    },
+};
+
+pub fn routes<T>(
+    users_repository: Arc<T>,
+    authentication: AuthenticationState<T>,
+    login_throttle: Arc<LoginThrottle>,
+    config: Arc<Config>,
+) -> Router
+where
+    T: UsersRepository + Send + Sync + 'static,
+{
+    let totp_use_case = Arc::new(TotpUseCase::new(
+        users_repository,
+        login_throttle,
+        Arc::clone(&config),
+    ));
+
+    Router::new()
+        .route("/enroll", post(enroll))
+        .route("/confirm", post(confirm))
+        .route("/disable", post(disable))
+        .route_layer(middleware::from_fn(require_session))
+        .route_layer(middleware::from_fn_with_state(
+            authentication,
+            user_authentication::<T>,
+        ))
+        .with_state(totp_use_case)
+}
+
+pub async fn enroll<T>(
+    State(totp_use_case): State<Arc<TotpUseCase<T>>>,
+    Extension(user_id): Extension<String>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    Json(step_up_model): Json<TotpStepUpModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+{
+    match totp_use_case
+        .enroll(user_id, step_up_model, &client_ip)
+        .await
+    {
+        Ok(enrollment) => (StatusCode::OK, Json(json!({"data": enrollment}))).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn confirm<T>(
+    State(totp_use_case): State<Arc<TotpUseCase<T>>>,
+    Extension(user_id): Extension<String>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    Json(code_model): Json<TotpCodeModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+{
+    match totp_use_case.confirm(user_id, code_model, &client_ip).await {
+        Ok(recovery_codes) => {
+            (StatusCode::OK, Json(json!({"data": recovery_codes}))).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn disable<T>(
+    State(totp_use_case): State<Arc<TotpUseCase<T>>>,
+    Extension(user_id): Extension<String>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    Json(step_up_model): Json<TotpStepUpModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+{
+    match totp_use_case
+        .disable(user_id, step_up_model, &client_ip)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Two-factor authentication disabled"})),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
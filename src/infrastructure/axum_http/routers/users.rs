@@ -1,60 +1,167 @@
 use std::sync::Arc;
 
 use axum::{
-    Json, Router,
-    extract::State,
+    Extension, Router,
+    extract::{Path, State},
     http::StatusCode,
+    middleware,
     response::IntoResponse,
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use serde_json::json;
 
 use crate::{
     application::usecases::users::UsersUseCase,
-    domain::{repositories::users::UsersRepository, value_objects::users::RegisterUserModel},
-    infrastructure::app_state::repositories::users::UsersAppState,
+    config::config_model::Config,
+    domain::{
+        entities::users::Role,
+        mailer::Mailer,
+        repositories::{audit::AuditRepository, users::UsersRepository},
+        value_objects::users::{
+            PasswordPolicyModel, RegisterUserModel, UpdateRoleModel, VerifyEmailModel,
+        },
+    },
+    infrastructure::{
+        axum_http::{
+            extractors::{Json, Query},
+            middleware::{AuthenticationState, ClientIp, require_role, user_authentication},
+            routers::{personal_access_tokens, sessions, totp},
+        },
+        login_throttle::LoginThrottle,
+    },
 };
 
-pub fn routes(users_state: Arc<UsersAppState>) -> Router {
-    let users_use_case = Arc::new(UsersUseCase::new(users_state));
+pub fn routes<T, M, A>(
+    users_repository: Arc<T>,
+    audit_repository: Arc<A>,
+    mailer: Arc<M>,
+    password_policy: Arc<PasswordPolicyModel>,
+    authentication: AuthenticationState<T>,
+    login_throttle: Arc<LoginThrottle>,
+    config: Arc<Config>,
+) -> Router
+where
+    T: UsersRepository + Send + Sync + 'static,
+    M: Mailer + Send + Sync + 'static,
+    A: AuditRepository + Send + Sync + 'static,
+{
+    let users_use_case = Arc::new(UsersUseCase::new(
+        Arc::clone(&users_repository),
+        Arc::clone(&audit_repository),
+        mailer,
+        password_policy,
+        Arc::clone(&config),
+    ));
+
+    let admin_routes = Router::new()
+        .route("/", get(list))
+        .route("/{id}/role", put(update_role))
+        .route_layer(middleware::from_fn_with_state(Role::Admin, require_role))
+        .route_layer(middleware::from_fn_with_state(
+            authentication.clone(),
+            user_authentication::<T>,
+        ));
 
     Router::new()
         .route("/register", post(register))
-        .route("/", get(list))
+        .route("/verify", get(verify_email))
+        .merge(admin_routes)
         .with_state(users_use_case)
+        .nest(
+            "/me/tokens",
+            personal_access_tokens::routes(authentication.clone()),
+        )
+        .nest(
+            "/me/sessions",
+            sessions::routes(
+                Arc::clone(&users_repository),
+                audit_repository,
+                authentication.clone(),
+            ),
+        )
+        .nest(
+            "/me/totp",
+            totp::routes(users_repository, authentication, login_throttle, config),
+        )
 }
 
-pub async fn register<T>(
-    State(users_use_case): State<Arc<UsersUseCase<T>>>,
+pub async fn register<T, M, A>(
+    State(users_use_case): State<Arc<UsersUseCase<T, M, A>>>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
     Json(register_user_model): Json<RegisterUserModel>,
 ) -> impl IntoResponse
 where
     T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
 {
-    match users_use_case.register(register_user_model).await {
+    match users_use_case
+        .register(register_user_model, client_ip)
+        .await
+    {
         Ok(_) => (
             StatusCode::CREATED,
             Json(json!({"message": "Create user success" })),
         )
             .into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Internal server error" })),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn verify_email<T, M, A>(
+    State(users_use_case): State<Arc<UsersUseCase<T, M, A>>>,
+    Query(verify_email_model): Query<VerifyEmailModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match users_use_case.verify_email(verify_email_model).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Verify email success" })),
         )
             .into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
-pub async fn list<T>(State(users_use_case): State<Arc<UsersUseCase<T>>>) -> impl IntoResponse
+pub async fn list<T, M, A>(
+    State(users_use_case): State<Arc<UsersUseCase<T, M, A>>>,
+) -> impl IntoResponse
 where
     T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
 {
     match users_use_case.list().await {
         Ok(users) => (StatusCode::OK, Json(json!({"data": users }))).into_response(),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Internal server error" })),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn update_role<T, M, A>(
+    State(users_use_case): State<Arc<UsersUseCase<T, M, A>>>,
+    Extension(user_id): Extension<String>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    Path(id): Path<String>,
+    Json(update_role_model): Json<UpdateRoleModel>,
+) -> impl IntoResponse
+where
+    T: UsersRepository + Send + Sync,
+    M: Mailer + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    match users_use_case
+        .update_role(user_id, id, update_role_model, client_ip)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Update role success" })),
         )
             .into_response(),
+        Err(e) => e.into_response(),
     }
 }
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+    routing::get,
+};
+
+use crate::infrastructure::jwt_authentication::jwt_keys::JwtKeys;
+
+pub fn routes(jwt_keys: Arc<JwtKeys>) -> Router {
+    Router::new()
+        .route("/jwks.json", get(jwks))
+        .with_state(jwt_keys)
+}
+
+// Short cache so verifiers pick up a newly added key well before it starts signing.
+pub async fn jwks(State(jwt_keys): State<Arc<JwtKeys>>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(jwt_keys.jwks().clone()),
+    )
+        .into_response()
+}
@@ -0,0 +1,13 @@
+use crate::domain::errors::DomainError;
+
+impl From<sqlx::Error> for DomainError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => DomainError::NotFound("Record not found".to_string()),
+            sqlx::Error::Database(ref e) if e.is_unique_violation() => {
+                DomainError::Conflict("Record already exists".to_string())
+            }
+            e => DomainError::Internal(e.into()),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domain::{
+    errors::DomainError,
+    mailer::{MailMessage, Mailer},
+};
+
+// Writes each message as a file in a Maildir (`tmp/`, `new/`, `cur/`) so local
+// development and tests can read mail without an SMTP server.
+pub struct FileMailer {
+    outbox_dir: PathBuf,
+    from: String,
+}
+
+impl FileMailer {
+    pub fn new(outbox_dir: impl Into<PathBuf>, from: impl Into<String>) -> Self {
+        Self {
+            outbox_dir: outbox_dir.into(),
+            from: from.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, message: MailMessage) -> Result<(), DomainError> {
+        for dir in ["tmp", "new", "cur"] {
+            tokio::fs::create_dir_all(self.outbox_dir.join(dir))
+                .await
+                .with_context(|| format!("Failed to create outbox {:?}", self.outbox_dir))?;
+        }
+
+        let now = Utc::now();
+        let file_name = format!("{}.{}.todos-api", now.timestamp(), Uuid::new_v4());
+        let content = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.from,
+            message.to,
+            message.subject,
+            now.to_rfc2822(),
+            message.body
+        );
+
+        // Maildir delivery: write to tmp/ and rename into new/ so readers never see half a file.
+        let tmp_path = self.outbox_dir.join("tmp").join(&file_name);
+        tokio::fs::write(&tmp_path, content)
+            .await
+            .with_context(|| format!("Failed to write mail {:?}", tmp_path))?;
+        tokio::fs::rename(&tmp_path, self.outbox_dir.join("new").join(&file_name))
+            .await
+            .context("Failed to deliver mail to outbox")?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,67 @@
 use serde::{Deserialize, Serialize};
 
+use crate::infrastructure::jwt_authentication::jwt_model::Passport;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct LoginModel {
     pub username: String,
     pub password: String,
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TotpLoginModel {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+// For clients without cookies, which get the refresh token in the login response body.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RefreshTokenModel {
+    pub refresh_token: String,
+}
+
+// Where a login or refresh came from, recorded on the session.
+#[derive(Debug, Clone)]
+pub struct DeviceModel {
+    pub ip_address: String,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum LoginOutcome {
+    Passport(Passport),
+    TotpRequired(TotpChallengeModel),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TotpChallengeModel {
+    pub totp_required: bool,
+    pub challenge_token: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct OidcAuthorizationModel {
+    pub authorization_url: String,
+    pub state_token: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OidcCallbackModel {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct LoginOptionsModel {
+    #[serde(default)]
+    pub response: LoginResponseMode,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginResponseMode {
+    #[default]
+    Cookie,
+    Json,
+}
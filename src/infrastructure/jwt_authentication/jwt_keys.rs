@@ -0,0 +1,169 @@
+use std::fs;
+
+use anyhow::{Context, Result, anyhow, bail};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+};
+use pkcs1::{RsaPublicKey, der::Decode};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::json;
+use spki::{ObjectIdentifier, SubjectPublicKeyInfoOwned, der::DecodePem};
+
+use crate::config::config_model::{Jwt, JwtAlgorithm};
+
+const RSA_ENCRYPTION_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+const ED25519_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+
+// Signs and verifies access tokens. With HS256 the shared `access_secret` is used and
+// nothing is published. With RS256/EdDSA every configured public key is accepted and
+// listed in the JWKS, so a new key can be published before it starts signing.
+pub struct JwtKeys {
+    header: Header,
+    encoding_key: EncodingKey,
+    decoding_keys: Vec<(Option<String>, DecodingKey)>,
+    validation: Validation,
+    jwks: JwkSet,
+}
+
+impl JwtKeys {
+    pub fn load(config: &Jwt) -> Result<Self> {
+        let algorithm = match config.algorithm {
+            JwtAlgorithm::Hs256 => Algorithm::HS256,
+            JwtAlgorithm::Rs256 => Algorithm::RS256,
+            JwtAlgorithm::EdDsa => Algorithm::EdDSA,
+        };
+
+        let mut validation = Validation::new(algorithm);
+        validation.set_issuer(&[&config.issuer]);
+        validation.set_audience(&[&config.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+
+        if algorithm == Algorithm::HS256 {
+            return Ok(Self {
+                header: Header::new(algorithm),
+                encoding_key: EncodingKey::from_secret(config.access_secret.as_bytes()),
+                decoding_keys: vec![(
+                    None,
+                    DecodingKey::from_secret(config.access_secret.as_bytes()),
+                )],
+                validation,
+                jwks: JwkSet { keys: Vec::new() },
+            });
+        }
+
+        let mut decoding_keys = Vec::new();
+        let mut jwks = Vec::new();
+        let mut encoding_key = None;
+
+        for key in &config.keys {
+            let public_pem = fs::read_to_string(&key.public_key_path)
+                .with_context(|| format!("Failed to read public key {}", key.public_key_path))?;
+            let jwk = public_jwk(algorithm, &key.kid, &public_pem)
+                .with_context(|| format!("Invalid public key {}", key.public_key_path))?;
+
+            decoding_keys.push((Some(key.kid.clone()), DecodingKey::from_jwk(&jwk)?));
+            jwks.push(jwk);
+
+            if key.kid == config.signing_kid
+                && let Some(path) = &key.private_key_path
+            {
+                let private_pem = fs::read(path)
+                    .with_context(|| format!("Failed to read private key {}", path))?;
+                let key = match algorithm {
+                    Algorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem),
+                    _ => EncodingKey::from_ed_pem(&private_pem),
+                }
+                .with_context(|| format!("Invalid private key {}", path))?;
+
+                encoding_key = Some(key);
+            }
+        }
+
+        let mut header = Header::new(algorithm);
+        header.kid = Some(config.signing_kid.clone());
+
+        let keys = Self {
+            header,
+            encoding_key: encoding_key
+                .ok_or_else(|| anyhow!("No private key for jwt.signing_kid"))?,
+            decoding_keys,
+            validation,
+            jwks: JwkSet { keys: jwks },
+        };
+
+        // Catches a private key that doesn't belong to the public key published with it.
+        let probe = keys.generate_token(&json!({
+            "iss": config.issuer,
+            "aud": config.audience,
+            "exp": usize::MAX,
+        }))?;
+        keys.verify_token::<serde_json::Value>(&probe)
+            .context("The jwt.signing_kid private key does not match its public key")?;
+
+        Ok(keys)
+    }
+
+    pub fn generate_token<C: Serialize>(&self, claims: &C) -> Result<String> {
+        Ok(encode(&self.header, claims, &self.encoding_key)?)
+    }
+
+    pub fn verify_token<C: DeserializeOwned>(&self, token: &str) -> Result<C> {
+        let header = decode_header(token)?;
+
+        let (_, key) = self
+            .decoding_keys
+            .iter()
+            .find(|(kid, _)| *kid == header.kid)
+            .ok_or_else(|| anyhow!("Unknown key id"))?;
+
+        Ok(decode::<C>(token, key, &self.validation)?.claims)
+    }
+
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+fn public_jwk(algorithm: Algorithm, kid: &str, pem: &str) -> Result<Jwk> {
+    let public_key = SubjectPublicKeyInfoOwned::from_pem(pem)?;
+    let key_bytes = public_key.subject_public_key.raw_bytes();
+
+    let (key_algorithm, parameters) = match (algorithm, public_key.algorithm.oid) {
+        (Algorithm::RS256, RSA_ENCRYPTION_OID) => {
+            let rsa = RsaPublicKey::from_der(key_bytes)?;
+
+            (
+                KeyAlgorithm::RS256,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(rsa.modulus.as_bytes()),
+                    e: URL_SAFE_NO_PAD.encode(rsa.public_exponent.as_bytes()),
+                }),
+            )
+        }
+        (Algorithm::EdDSA, ED25519_OID) => (
+            KeyAlgorithm::EdDSA,
+            AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(key_bytes),
+            }),
+        ),
+        _ => bail!("Key type does not match the {:?} algorithm", algorithm),
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    })
+}
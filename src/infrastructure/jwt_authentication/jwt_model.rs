@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domain::entities::users::Role;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Passport {
     pub refresh_token: String,
@@ -8,7 +10,44 @@ pub struct Passport {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Claims {
+    pub iss: String,
+    pub aud: String,
+    pub sub: String,
+    pub role: Role,
+    pub jti: String,
+    pub exp: usize,
+    pub iat: usize,
+    // The session an access token belongs to, so revoking the session rejects it at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+}
+
+// Single-purpose tokens such as the 2FA login challenge or an email verification
+// link. They have no `role` or `jti`, so they never decode as access `Claims`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ChallengeClaims {
     pub sub: String,
+    pub purpose: String,
+    pub exp: usize,
+    pub iat: usize,
+    // An SSO identity matched by email, linked only once the challenge is passed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_identity: Option<IdentityClaims>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct IdentityClaims {
+    pub issuer: String,
+    pub subject: String,
+}
+
+// Carried in a short-lived cookie from the redirect to the provider until its callback.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OidcStateClaims {
+    pub provider: String,
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
     pub exp: usize,
     pub iat: usize,
 }
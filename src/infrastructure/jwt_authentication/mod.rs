@@ -1,12 +1,12 @@
 pub mod authentication_model;
+pub mod jwt_keys;
 pub mod jwt_model;
 
 use anyhow::{Ok, Result};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Serialize, de::DeserializeOwned};
 
-use crate::infrastructure::jwt_authentication::jwt_model::Claims;
-
-pub fn generate_token(secret: String, claims: &Claims) -> Result<String> {
+pub fn generate_token<C: Serialize>(secret: String, claims: &C) -> Result<String> {
     let token = encode(
         &Header::default(),
         claims,
@@ -16,11 +16,16 @@ pub fn generate_token(secret: String, claims: &Claims) -> Result<String> {
     Ok(token)
 }
 
-pub fn verify_toke(secret: String, token: String) -> Result<Claims> {
-    let token = decode::<Claims>(
+// For tokens that never leave this service (refresh, 2FA challenge, email
+// verification); access tokens go through `JwtKeys`, which also checks `iss`/`aud`.
+pub fn verify_toke<C: DeserializeOwned>(secret: String, token: String) -> Result<C> {
+    let mut validation = Validation::default();
+    validation.validate_aud = false;
+
+    let token = decode::<C>(
         &token,
         &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::default(),
+        &validation,
     )?;
 
     Ok(token.claims)
@@ -0,0 +1,98 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::config_model::LoginThrottle as LoginThrottleConfig, domain::errors::DomainError,
+};
+
+struct Attempts {
+    failures: u32,
+    last_failure: Instant,
+    blocked_until: Option<Instant>,
+}
+
+// Counts are kept in process memory, so each server instance throttles on its own
+// and a restart clears them.
+pub struct LoginThrottle {
+    config: LoginThrottleConfig,
+    attempts: Mutex<HashMap<String, Attempts>>,
+}
+
+impl LoginThrottle {
+    pub fn new(config: LoginThrottleConfig) -> Self {
+        Self {
+            config,
+            attempts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn retry_after(&self, keys: &[String]) -> Option<Duration> {
+        let attempts = self.attempts.lock().unwrap();
+        let now = Instant::now();
+
+        keys.iter()
+            .filter_map(|key| attempts.get(key)?.blocked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+            .max()
+    }
+
+    pub fn check(&self, keys: &[String], message: &str) -> Result<(), DomainError> {
+        match self.retry_after(keys) {
+            Some(retry_after) => Err(DomainError::TooManyRequests {
+                message: message.to_string(),
+                // Round up so clients never retry a moment too early.
+                retry_after_secs: retry_after.as_secs() + 1,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn record_failure(&self, keys: &[String]) {
+        let mut attempts = self.attempts.lock().unwrap();
+        let now = Instant::now();
+        let forget_after = Duration::from_secs(self.config.lockout_secs);
+
+        attempts.retain(|_, a| {
+            now.duration_since(a.last_failure) < forget_after
+                || a.blocked_until.is_some_and(|until| until > now)
+        });
+
+        for key in keys {
+            let entry = attempts.entry(key.clone()).or_insert(Attempts {
+                failures: 0,
+                last_failure: now,
+                blocked_until: None,
+            });
+
+            entry.failures += 1;
+            entry.last_failure = now;
+            entry.blocked_until = self.delay(entry.failures).map(|delay| now + delay);
+        }
+    }
+
+    pub fn reset(&self, key: &str) {
+        self.attempts.lock().unwrap().remove(key);
+    }
+
+    fn delay(&self, failures: u32) -> Option<Duration> {
+        if failures >= self.config.lockout_attempts {
+            return Some(Duration::from_secs(self.config.lockout_secs));
+        }
+        if failures <= self.config.free_attempts {
+            return None;
+        }
+
+        let exponent = (failures - self.config.free_attempts - 1).min(32);
+        let delay = self
+            .config
+            .base_delay_secs
+            .saturating_mul(1u64 << exponent)
+            .min(self.config.max_delay_secs);
+
+        Some(Duration::from_secs(delay))
+    }
+}
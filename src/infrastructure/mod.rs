@@ -1,3 +1,12 @@
 pub mod app_state;
+pub mod argon2_hashing;
 pub mod axum_http;
+pub mod database_errors;
+pub mod file_mailer;
 pub mod jwt_authentication;
+pub mod login_throttle;
+pub mod oidc;
+pub mod postgres;
+pub mod sqlite;
+pub mod token_hashing;
+pub mod totp;
@@ -0,0 +1,231 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use reqwest::Url;
+use serde::{Deserialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use tokio::sync::OnceCell;
+
+use crate::{config::config_model::OidcProvider, infrastructure::token_hashing};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+pub struct OidcIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    preferred_username: Option<String>,
+}
+
+// Talks to a single provider. The discovery document is fetched on first use and kept,
+// while the JWKS is fetched on every login so rotated signing keys are picked up.
+pub struct OidcClient {
+    provider: OidcProvider,
+    http: reqwest::Client,
+    metadata: OnceCell<ProviderMetadata>,
+}
+
+impl OidcClient {
+    pub fn new(provider: OidcProvider) -> Self {
+        Self {
+            provider,
+            http: reqwest::Client::new(),
+            metadata: OnceCell::new(),
+        }
+    }
+
+    pub fn provider(&self) -> &OidcProvider {
+        &self.provider
+    }
+
+    pub async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_verifier: &str,
+    ) -> Result<String> {
+        let metadata = self.metadata().await?;
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.provider.client_id.as_str()),
+                ("redirect_uri", self.provider.redirect_url.as_str()),
+                ("scope", self.provider.scopes.join(" ").as_str()),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", code_challenge.as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .context("Invalid authorization_endpoint")?;
+
+        Ok(url.into())
+    }
+
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<OidcIdentity> {
+        let metadata = self.metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.provider.redirect_url.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+
+        let mut request = self.http.post(&metadata.token_endpoint);
+        // Public clients have no secret and rely on PKCE alone.
+        if self.provider.client_secret.is_empty() {
+            form.push(("client_id", self.provider.client_id.as_str()));
+        } else {
+            request =
+                request.basic_auth(&self.provider.client_id, Some(&self.provider.client_secret));
+        }
+
+        let response = request
+            .form(&form)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .context("Token request failed")?;
+        if !response.status().is_success() {
+            bail!("Token endpoint answered {}", response.status());
+        }
+
+        let tokens: TokenResponse = response.json().await.context("Invalid token response")?;
+
+        self.verify_id_token(metadata, &tokens.id_token, nonce)
+            .await
+    }
+
+    async fn verify_id_token(
+        &self,
+        metadata: &ProviderMetadata,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<OidcIdentity> {
+        let header = decode_header(id_token)?;
+
+        // Only public-key algorithms, so an HS256 token can't be checked against a public key.
+        if !matches!(
+            header.alg,
+            Algorithm::RS256
+                | Algorithm::RS384
+                | Algorithm::RS512
+                | Algorithm::PS256
+                | Algorithm::PS384
+                | Algorithm::PS512
+                | Algorithm::ES256
+                | Algorithm::ES384
+                | Algorithm::EdDSA
+        ) {
+            bail!("Unsupported ID token algorithm {:?}", header.alg);
+        }
+
+        let jwks: JwkSet = self.get_json(&metadata.jwks_uri).await?;
+        let jwk = match &header.kid {
+            Some(kid) => jwks.find(kid),
+            None if jwks.keys.len() == 1 => jwks.keys.first(),
+            None => None,
+        }
+        .ok_or_else(|| anyhow!("No JWKS key matches the ID token"))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.provider.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims =
+            decode::<IdTokenClaims>(id_token, &DecodingKey::from_jwk(jwk)?, &validation)?.claims;
+
+        if !claims
+            .nonce
+            .is_some_and(|n| token_hashing::constant_time_eq(n.as_bytes(), nonce.as_bytes()))
+        {
+            bail!("ID token nonce does not match");
+        }
+
+        Ok(OidcIdentity {
+            issuer: metadata.issuer.clone(),
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            preferred_username: claims.preferred_username,
+        })
+    }
+
+    async fn metadata(&self) -> Result<&ProviderMetadata> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.provider.issuer.trim_end_matches('/')
+                );
+                let metadata: ProviderMetadata = self.get_json(&url).await?;
+
+                // Discovery requires the document to name exactly the issuer it was fetched for.
+                if metadata.issuer != self.provider.issuer {
+                    bail!(
+                        "Provider {} reports issuer {}",
+                        self.provider.name,
+                        metadata.issuer
+                    );
+                }
+
+                Ok(metadata)
+            })
+            .await
+    }
+
+    async fn get_json<R: DeserializeOwned>(&self, url: &str) -> Result<R> {
+        let response = self
+            .http
+            .get(url)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        if !response.status().is_success() {
+            bail!("{} answered {}", url, response.status());
+        }
+
+        response
+            .json()
+            .await
+            .with_context(|| format!("Invalid JSON from {}", url))
+    }
+}
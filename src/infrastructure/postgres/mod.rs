@@ -0,0 +1,19 @@
+pub mod repositories;
+
+use anyhow::Result;
+use sqlx::{PgPool, postgres::PgPoolOptions};
+
+pub async fn connect(url: &str, max_connections: u32) -> Result<PgPool> {
+    let pool = PgPoolOptions::new()
+        .max_connections(max_connections)
+        .connect(url)
+        .await?;
+
+    Ok(pool)
+}
+
+pub async fn migrate(pool: &PgPool) -> Result<()> {
+    sqlx::migrate!("./migrations/postgres").run(pool).await?;
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, QueryBuilder, Row, postgres::PgRow};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::audit_events::{
+        AddAuditEventEntity, AuditEventEntity, AuditEventKind, AuditQueryEntity,
+    },
+    errors::DomainError,
+    repositories::audit::AuditRepository,
+};
+
+#[derive(Clone)]
+pub struct AuditPostgres {
+    pool: PgPool,
+}
+
+impl AuditPostgres {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn to_entity(row: PgRow) -> Result<AuditEventEntity, DomainError> {
+    let details: Option<String> = row.try_get("details")?;
+
+    Ok(AuditEventEntity {
+        id: row.try_get("id")?,
+        event: row
+            .try_get::<String, _>("event")?
+            .parse::<AuditEventKind>()?,
+        actor_id: row.try_get("actor_id")?,
+        target_id: row.try_get("target_id")?,
+        ip_address: row.try_get("ip_address")?,
+        details: details
+            .map(|d| serde_json::from_str(&d))
+            .transpose()
+            .map_err(anyhow::Error::from)?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[async_trait]
+impl AuditRepository for AuditPostgres {
+    async fn add(&self, event: AddAuditEventEntity) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO audit_events (id, event, actor_id, target_id, ip_address, details, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(event.event.as_str())
+        .bind(event.actor_id)
+        .bind(event.target_id)
+        .bind(event.ip_address)
+        .bind(event.details.map(|d| d.to_string()))
+        .bind(chrono::Utc::now().naive_utc())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn query(&self, query: AuditQueryEntity) -> Result<Vec<AuditEventEntity>, DomainError> {
+        let mut builder =
+            QueryBuilder::<sqlx::Postgres>::new("SELECT * FROM audit_events WHERE 1 = 1");
+
+        if let Some(event) = query.event {
+            builder.push(" AND event = ").push_bind(event.as_str());
+        }
+        if let Some(actor_id) = query.actor_id {
+            builder.push(" AND actor_id = ").push_bind(actor_id);
+        }
+        if let Some(created_after) = query.created_after {
+            builder.push(" AND created_at > ").push_bind(created_after);
+        }
+        if let Some(created_before) = query.created_before {
+            builder.push(" AND created_at < ").push_bind(created_before);
+        }
+
+        builder
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(query.limit as i64);
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+
+        rows.into_iter().map(to_entity).collect()
+    }
+}
@@ -0,0 +1,256 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, QueryBuilder, Row, postgres::PgRow};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::todos::{
+        AddTodoEntity, DueAtKey, SortDirection, TodoEntity, TodoQueryEntity, TodoSortKey,
+        UpdateTodoEntity,
+    },
+    errors::DomainError,
+    repositories::todos::TodosRepository,
+};
+
+#[derive(Clone)]
+pub struct TodosPostgres {
+    pool: PgPool,
+}
+
+impl TodosPostgres {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn to_entity(row: PgRow) -> Result<TodoEntity, DomainError> {
+    Ok(TodoEntity {
+        id: row.try_get("id")?,
+        title: row.try_get("title")?,
+        user_id: row.try_get("user_id")?,
+        completed: row.try_get("completed")?,
+        due_at: row.try_get("due_at")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+#[async_trait]
+impl TodosRepository for TodosPostgres {
+    async fn list(&self, user_id: String) -> Result<Vec<TodoEntity>, DomainError> {
+        let rows = sqlx::query("SELECT * FROM todos WHERE user_id = $1 ORDER BY created_at")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter().map(to_entity).collect()
+    }
+
+    async fn query(
+        &self,
+        user_id: String,
+        query: TodoQueryEntity,
+    ) -> Result<Vec<TodoEntity>, DomainError> {
+        let mut builder =
+            QueryBuilder::<sqlx::Postgres>::new("SELECT * FROM todos WHERE user_id = ");
+        builder.push_bind(user_id);
+
+        if let Some(completed) = query.completed {
+            builder.push(" AND completed = ").push_bind(completed);
+        }
+        if let Some(created_after) = query.created_after {
+            builder.push(" AND created_at > ").push_bind(created_after);
+        }
+        if let Some(created_before) = query.created_before {
+            builder.push(" AND created_at < ").push_bind(created_before);
+        }
+        if let Some(due_after) = query.due_after {
+            builder.push(" AND due_at >= ").push_bind(due_after);
+        }
+        if let Some(due_before) = query.due_before {
+            builder.push(" AND due_at < ").push_bind(due_before);
+        }
+        match query.overdue {
+            Some(true) => {
+                builder
+                    .push(" AND completed = FALSE AND due_at < ")
+                    .push_bind(chrono::Utc::now());
+            }
+            Some(false) => {
+                builder
+                    .push(" AND (completed = TRUE OR due_at IS NULL OR due_at >= ")
+                    .push_bind(chrono::Utc::now())
+                    .push(")");
+            }
+            None => {}
+        }
+        // ILIKE would follow the database locale, so fold ASCII letters by hand to match
+        // the other backends.
+        if let Some(q) = query.q {
+            let pattern = format!(
+                "%{}%",
+                q.to_ascii_lowercase()
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            );
+            builder
+                .push(
+                    " AND translate(title, 'ABCDEFGHIJKLMNOPQRSTUVWXYZ', 'abcdefghijklmnopqrstuvwxyz') LIKE ",
+                )
+                .push_bind(pattern)
+                .push(" ESCAPE '\\'");
+        }
+
+        let column = query.sort.column();
+        let nulls = query.sort.nulls(query.direction);
+        let (operator, direction) = match query.direction {
+            SortDirection::Asc => (">", "ASC"),
+            SortDirection::Desc => ("<", "DESC"),
+        };
+
+        if let Some(cursor) = query.cursor {
+            match cursor.key {
+                TodoSortKey::CreatedAt(value) | TodoSortKey::UpdatedAt(value) => {
+                    builder
+                        .push(format!(" AND ({}, id) {} (", column, operator))
+                        .push_bind(value)
+                        .push(", ")
+                        .push_bind(cursor.id)
+                        .push(")");
+                }
+                TodoSortKey::Title(value) => {
+                    builder
+                        .push(format!(" AND ({}, id) {} (", column, operator))
+                        .push_bind(value)
+                        .push(", ")
+                        .push_bind(cursor.id)
+                        .push(")");
+                }
+                // NULL sorts after every due date, so the row comparison alone can't place it.
+                TodoSortKey::DueAt(DueAtKey(Some(value))) => {
+                    if query.direction == SortDirection::Asc {
+                        builder.push(" AND (due_at IS NULL OR (due_at, id) > (");
+                    } else {
+                        builder.push(" AND ((due_at, id) < (");
+                    }
+                    builder
+                        .push_bind(value)
+                        .push(", ")
+                        .push_bind(cursor.id)
+                        .push("))");
+                }
+                TodoSortKey::DueAt(DueAtKey(None)) => {
+                    if query.direction == SortDirection::Asc {
+                        builder.push(" AND (due_at IS NULL AND id > ");
+                    } else {
+                        builder.push(" AND (due_at IS NOT NULL OR id < ");
+                    }
+                    builder.push_bind(cursor.id).push(")");
+                }
+            }
+        }
+
+        builder
+            .push(format!(
+                " ORDER BY {} {}{}, id {} LIMIT ",
+                column, direction, nulls, direction
+            ))
+            .push_bind(query.limit as i64);
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+
+        rows.into_iter().map(to_entity).collect()
+    }
+
+    async fn get(&self, user_id: String, id: String) -> Result<TodoEntity, DomainError> {
+        let row = sqlx::query("SELECT * FROM todos WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("Todo not found".to_string()))?;
+
+        to_entity(row)
+    }
+
+    async fn add(
+        &self,
+        user_id: String,
+        payload: AddTodoEntity,
+    ) -> Result<TodoEntity, DomainError> {
+        let now = chrono::Utc::now().naive_utc();
+
+        let row = sqlx::query(
+            "INSERT INTO todos (id, title, user_id, completed, due_at, created_at, updated_at)
+             VALUES ($1, $2, $3, FALSE, $4, $5, $6)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(payload.title)
+        .bind(user_id)
+        .bind(payload.due_at)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        to_entity(row)
+    }
+
+    async fn update(
+        &self,
+        user_id: String,
+        id: String,
+        payload: UpdateTodoEntity,
+    ) -> Result<TodoEntity, DomainError> {
+        let row = sqlx::query(
+            "UPDATE todos
+             SET title = COALESCE($1, title), completed = COALESCE($2, completed),
+                 due_at = CASE WHEN $3 THEN $4 ELSE due_at END, updated_at = $5
+             WHERE id = $6 AND user_id = $7
+             RETURNING *",
+        )
+        .bind(payload.title)
+        .bind(payload.completed)
+        .bind(payload.due_at.is_some())
+        .bind(payload.due_at.flatten())
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("Todo not found".to_string()))?;
+
+        to_entity(row)
+    }
+
+    async fn to_completed(&self, user_id: String, id: String) -> Result<TodoEntity, DomainError> {
+        let row = sqlx::query(
+            "UPDATE todos SET completed = TRUE, updated_at = $1
+             WHERE id = $2 AND user_id = $3
+             RETURNING *",
+        )
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("Todo not found".to_string()))?;
+
+        to_entity(row)
+    }
+
+    async fn delete(&self, user_id: String, id: String) -> Result<(), DomainError> {
+        let result = sqlx::query("DELETE FROM todos WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound("Todo not found".to_string()));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,712 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Row, postgres::PgRow};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::{
+        password_reset_tokens::PasswordResetTokenEntity,
+        personal_access_tokens::{PersonalAccessTokenEntity, TokenScope},
+        recovery_codes::RecoveryCodeEntity,
+        refresh_tokens::RefreshTokenEntity,
+        sessions::SessionEntity,
+        user_identities::UserIdentityEntity,
+        users::{RegisterUserEntity, Role, UpdateUserEntity, UserEntity},
+    },
+    errors::DomainError,
+    repositories::users::UsersRepository,
+};
+
+#[derive(Clone)]
+pub struct UsersPostgres {
+    pool: PgPool,
+}
+
+impl UsersPostgres {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn to_entity(row: PgRow) -> Result<UserEntity, DomainError> {
+    Ok(UserEntity {
+        id: row.try_get("id")?,
+        username: row.try_get("username")?,
+        password_hash: row.try_get("password_hash")?,
+        role: row.try_get::<String, _>("role")?.parse::<Role>()?,
+        email: row.try_get("email")?,
+        verified: row.try_get("verified")?,
+        display_name: row.try_get("display_name")?,
+        timezone: row.try_get("timezone")?,
+        locale: row.try_get("locale")?,
+        totp_secret: row.try_get("totp_secret")?,
+        totp_enabled: row.try_get("totp_enabled")?,
+        totp_last_step: row.try_get("totp_last_step")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+fn to_refresh_token_entity(row: PgRow) -> Result<RefreshTokenEntity, DomainError> {
+    Ok(RefreshTokenEntity {
+        jti: row.try_get("jti")?,
+        family_id: row.try_get("family_id")?,
+        user_id: row.try_get("user_id")?,
+        expires_at: row.try_get("expires_at")?,
+        created_at: row.try_get("created_at")?,
+        rotated_at: row.try_get("rotated_at")?,
+        revoked_at: row.try_get("revoked_at")?,
+    })
+}
+
+fn to_session_entity(row: PgRow) -> Result<SessionEntity, DomainError> {
+    Ok(SessionEntity {
+        id: row.try_get("id")?,
+        user_id: row.try_get("user_id")?,
+        user_agent: row.try_get("user_agent")?,
+        ip_address: row.try_get("ip_address")?,
+        created_at: row.try_get("created_at")?,
+        last_used_at: row.try_get("last_used_at")?,
+    })
+}
+
+fn to_personal_access_token_entity(row: PgRow) -> Result<PersonalAccessTokenEntity, DomainError> {
+    let scopes: String = row.try_get("scopes")?;
+
+    Ok(PersonalAccessTokenEntity {
+        id: row.try_get("id")?,
+        user_id: row.try_get("user_id")?,
+        name: row.try_get("name")?,
+        token_hash: row.try_get("token_hash")?,
+        scopes: scopes
+            .split_whitespace()
+            .map(|s| s.parse::<TokenScope>())
+            .collect::<Result<_, _>>()?,
+        expires_at: row.try_get("expires_at")?,
+        last_used_at: row.try_get("last_used_at")?,
+        created_at: row.try_get("created_at")?,
+        revoked_at: row.try_get("revoked_at")?,
+    })
+}
+
+#[async_trait]
+impl UsersRepository for UsersPostgres {
+    async fn register(&self, payload: RegisterUserEntity) -> Result<(), DomainError> {
+        let now = chrono::Utc::now().naive_utc();
+
+        sqlx::query(
+            "INSERT INTO users (id, username, password_hash, role, email, verified, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(payload.username)
+        .bind(payload.password_hash)
+        .bind(payload.role.as_str())
+        .bind(payload.email)
+        .bind(payload.verified)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match DomainError::from(e) {
+            DomainError::Conflict(_) => {
+                DomainError::Conflict("Username already exists".to_string())
+            }
+            e => e,
+        })?;
+
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<UserEntity>, DomainError> {
+        let rows = sqlx::query("SELECT * FROM users ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter().map(to_entity).collect()
+    }
+
+    async fn find_by_id(&self, id: String) -> Result<Option<UserEntity>, DomainError> {
+        let row = sqlx::query("SELECT * FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(to_entity).transpose()
+    }
+
+    async fn find_by_username(&self, username: String) -> Result<Option<UserEntity>, DomainError> {
+        let row = sqlx::query("SELECT * FROM users WHERE lower(username) = lower($1)")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(to_entity).transpose()
+    }
+
+    async fn find_by_email(&self, email: String) -> Result<Vec<UserEntity>, DomainError> {
+        let rows = sqlx::query("SELECT * FROM users WHERE lower(email) = lower($1)")
+            .bind(email)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter().map(to_entity).collect()
+    }
+
+    async fn update(
+        &self,
+        id: String,
+        payload: UpdateUserEntity,
+    ) -> Result<UserEntity, DomainError> {
+        let row = sqlx::query(
+            "UPDATE users
+             SET username = COALESCE($1, username),
+                 display_name = CASE WHEN $2 THEN $3 ELSE display_name END,
+                 timezone = COALESCE($4, timezone), locale = COALESCE($5, locale), updated_at = $6
+             WHERE id = $7
+             RETURNING *",
+        )
+        .bind(payload.username)
+        .bind(payload.display_name.is_some())
+        .bind(payload.display_name.flatten())
+        .bind(payload.timezone)
+        .bind(payload.locale)
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match DomainError::from(e) {
+            DomainError::Conflict(_) => {
+                DomainError::Conflict("Username already exists".to_string())
+            }
+            e => e,
+        })?
+        .ok_or_else(|| DomainError::NotFound("User not found".to_string()))?;
+
+        to_entity(row)
+    }
+
+    // Tokens, recovery codes and reset tokens go with the row through `ON DELETE CASCADE`.
+    async fn delete(&self, id: String) -> Result<(), DomainError> {
+        let result = sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound("User not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn update_password(&self, id: String, password_hash: String) -> Result<(), DomainError> {
+        sqlx::query("UPDATE users SET password_hash = $1, updated_at = $2 WHERE id = $3")
+            .bind(password_hash)
+            .bind(chrono::Utc::now().naive_utc())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn update_role(&self, id: String, role: Role) -> Result<(), DomainError> {
+        let result = sqlx::query("UPDATE users SET role = $1, updated_at = $2 WHERE id = $3")
+            .bind(role.as_str())
+            .bind(chrono::Utc::now().naive_utc())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound("User not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn mark_verified(&self, id: String) -> Result<(), DomainError> {
+        let result = sqlx::query("UPDATE users SET verified = $1, updated_at = $2 WHERE id = $3")
+            .bind(true)
+            .bind(chrono::Utc::now().naive_utc())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound("User not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn update_totp(
+        &self,
+        id: String,
+        secret: Option<String>,
+        enabled: bool,
+    ) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE users SET totp_secret = $1, totp_enabled = $2, updated_at = $3 WHERE id = $4",
+        )
+        .bind(secret)
+        .bind(enabled)
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn record_totp_step(&self, id: String, step: i64) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "UPDATE users SET totp_last_step = $1
+             WHERE id = $2 AND (totp_last_step IS NULL OR totp_last_step < $3)",
+        )
+        .bind(step)
+        .bind(id)
+        .bind(step)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: String,
+        codes: Vec<RecoveryCodeEntity>,
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for code in codes {
+            sqlx::query(
+                "INSERT INTO recovery_codes (id, user_id, code_hash, created_at, used_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(code.id)
+            .bind(code.user_id)
+            .bind(code.code_hash)
+            .bind(code.created_at)
+            .bind(code.used_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn use_recovery_code(
+        &self,
+        user_id: String,
+        code_hash: String,
+    ) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "UPDATE recovery_codes SET used_at = $1
+             WHERE user_id = $2 AND code_hash = $3 AND used_at IS NULL",
+        )
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn add_refresh_token(&self, token: RefreshTokenEntity) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO refresh_tokens
+                (jti, family_id, user_id, expires_at, created_at, rotated_at, revoked_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(token.jti)
+        .bind(token.family_id)
+        .bind(token.user_id)
+        .bind(token.expires_at)
+        .bind(token.created_at)
+        .bind(token.rotated_at)
+        .bind(token.revoked_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_refresh_token(
+        &self,
+        jti: String,
+    ) -> Result<Option<RefreshTokenEntity>, DomainError> {
+        let row = sqlx::query("SELECT * FROM refresh_tokens WHERE jti = $1")
+            .bind(jti)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(to_refresh_token_entity).transpose()
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        jti: String,
+        next: RefreshTokenEntity,
+    ) -> Result<bool, DomainError> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            "UPDATE refresh_tokens SET rotated_at = $1
+             WHERE jti = $2 AND rotated_at IS NULL AND revoked_at IS NULL",
+        )
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(jti)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "INSERT INTO refresh_tokens
+                (jti, family_id, user_id, expires_at, created_at, rotated_at, revoked_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(next.jti)
+        .bind(next.family_id)
+        .bind(next.user_id)
+        .bind(next.expires_at)
+        .bind(next.created_at)
+        .bind(next.rotated_at)
+        .bind(next.revoked_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn revoke_refresh_token_family(&self, family_id: String) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = $1
+             WHERE family_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(family_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn revoke_all_refresh_tokens(&self, user_id: String) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = $1
+             WHERE user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn revoke_other_refresh_tokens(
+        &self,
+        user_id: String,
+        keep_family_id: String,
+    ) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = $1
+             WHERE user_id = $2 AND family_id <> $3 AND revoked_at IS NULL",
+        )
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(user_id)
+        .bind(keep_family_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn add_session(&self, session: SessionEntity) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO sessions (id, user_id, user_agent, ip_address, created_at, last_used_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(session.id)
+        .bind(session.user_id)
+        .bind(session.user_agent)
+        .bind(session.ip_address)
+        .bind(session.created_at)
+        .bind(session.last_used_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn touch_session(
+        &self,
+        id: String,
+        ip_address: String,
+        user_agent: Option<String>,
+    ) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE sessions SET ip_address = $1, user_agent = $2, last_used_at = $3
+             WHERE id = $4",
+        )
+        .bind(ip_address)
+        .bind(user_agent)
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_sessions(&self, user_id: String) -> Result<Vec<SessionEntity>, DomainError> {
+        let rows = sqlx::query(
+            "SELECT * FROM sessions
+             WHERE user_id = $1 AND EXISTS (
+                 SELECT 1 FROM refresh_tokens
+                 WHERE refresh_tokens.family_id = sessions.id AND rotated_at IS NULL
+                     AND revoked_at IS NULL AND expires_at > $2
+             )
+             ORDER BY last_used_at DESC",
+        )
+        .bind(user_id)
+        .bind(chrono::Utc::now().naive_utc())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(to_session_entity).collect()
+    }
+
+    async fn is_session_active(&self, id: String) -> Result<bool, DomainError> {
+        let active: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM refresh_tokens
+                 WHERE family_id = $1 AND rotated_at IS NULL AND revoked_at IS NULL
+                     AND expires_at > $2
+             )",
+        )
+        .bind(id)
+        .bind(chrono::Utc::now().naive_utc())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(active)
+    }
+
+    async fn add_password_reset_token(
+        &self,
+        token: PasswordResetTokenEntity,
+    ) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO password_reset_tokens
+                (id, user_id, token_hash, expires_at, created_at, used_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(token.id)
+        .bind(token.user_id)
+        .bind(token.token_hash)
+        .bind(token.expires_at)
+        .bind(token.created_at)
+        .bind(token.used_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn consume_password_reset_token(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<String>, DomainError> {
+        let now = chrono::Utc::now().naive_utc();
+        let mut tx = self.pool.begin().await?;
+
+        let user_id: Option<String> = sqlx::query_scalar(
+            "UPDATE password_reset_tokens SET used_at = $1
+             WHERE token_hash = $2 AND used_at IS NULL AND expires_at > $3
+             RETURNING user_id",
+        )
+        .bind(now)
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(user_id) = &user_id {
+            sqlx::query(
+                "UPDATE password_reset_tokens SET used_at = $1
+                 WHERE user_id = $2 AND used_at IS NULL",
+            )
+            .bind(now)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(user_id)
+    }
+
+    async fn add_personal_access_token(
+        &self,
+        token: PersonalAccessTokenEntity,
+    ) -> Result<(), DomainError> {
+        let scopes = token
+            .scopes
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        sqlx::query(
+            "INSERT INTO personal_access_tokens
+                (id, user_id, name, token_hash, scopes, expires_at, last_used_at, created_at, revoked_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(token.id)
+        .bind(token.user_id)
+        .bind(token.name)
+        .bind(token.token_hash)
+        .bind(scopes)
+        .bind(token.expires_at)
+        .bind(token.last_used_at)
+        .bind(token.created_at)
+        .bind(token.revoked_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_personal_access_tokens(
+        &self,
+        user_id: String,
+    ) -> Result<Vec<PersonalAccessTokenEntity>, DomainError> {
+        let rows = sqlx::query(
+            "SELECT * FROM personal_access_tokens
+             WHERE user_id = $1 AND revoked_at IS NULL
+             ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(to_personal_access_token_entity)
+            .collect()
+    }
+
+    async fn find_personal_access_token(
+        &self,
+        token_hash: String,
+    ) -> Result<Option<PersonalAccessTokenEntity>, DomainError> {
+        let row = sqlx::query("SELECT * FROM personal_access_tokens WHERE token_hash = $1")
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(to_personal_access_token_entity).transpose()
+    }
+
+    async fn touch_personal_access_token(&self, id: String) -> Result<(), DomainError> {
+        sqlx::query("UPDATE personal_access_tokens SET last_used_at = $1 WHERE id = $2")
+            .bind(chrono::Utc::now().naive_utc())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn revoke_personal_access_token(
+        &self,
+        user_id: String,
+        id: String,
+    ) -> Result<(), DomainError> {
+        let result = sqlx::query(
+            "UPDATE personal_access_tokens SET revoked_at = $1
+             WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL",
+        )
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound("Token not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn revoke_all_personal_access_tokens(&self, user_id: String) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE personal_access_tokens SET revoked_at = $1
+             WHERE user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(chrono::Utc::now().naive_utc())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn add_identity(&self, identity: UserIdentityEntity) -> Result<(), DomainError> {
+        sqlx::query(
+            "INSERT INTO user_identities (issuer, subject, user_id, created_at)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(identity.issuer)
+        .bind(identity.subject)
+        .bind(identity.user_id)
+        .bind(identity.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match DomainError::from(e) {
+            DomainError::Conflict(_) => {
+                DomainError::Conflict("Identity is already linked".to_string())
+            }
+            e => e,
+        })?;
+
+        Ok(())
+    }
+
+    async fn find_by_identity(
+        &self,
+        issuer: String,
+        subject: String,
+    ) -> Result<Option<UserEntity>, DomainError> {
+        let row = sqlx::query(
+            "SELECT users.* FROM users
+             JOIN user_identities ON user_identities.user_id = users.id
+             WHERE user_identities.issuer = $1 AND user_identities.subject = $2",
+        )
+        .bind(issuer)
+        .bind(subject)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(to_entity).transpose()
+    }
+}
@@ -0,0 +1,21 @@
+pub mod repositories;
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+
+pub async fn connect(url: &str) -> Result<SqlitePool> {
+    let options = SqliteConnectOptions::from_str(url)?
+        .create_if_missing(true)
+        .foreign_keys(true);
+
+    let pool = SqlitePoolOptions::new().connect_with(options).await?;
+
+    sqlx::migrate!("./migrations/sqlite").run(&pool).await?;
+
+    Ok(pool)
+}
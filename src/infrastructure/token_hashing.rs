@@ -0,0 +1,22 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use rand::{RngCore, rngs::OsRng};
+use sha2::{Digest, Sha256};
+
+const TOKEN_BYTES: usize = 32;
+
+pub fn generate(prefix: &str) -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+
+    format!("{}{}", prefix, URL_SAFE_NO_PAD.encode(bytes))
+}
+
+// Generated tokens carry 256 bits of entropy, so a fast hash is enough and
+// lets the hash itself be used as the lookup key.
+pub fn hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -1,3 +1,4 @@
 pub mod application;
+pub mod config;
 pub mod domain;
 pub mod infrastructure;
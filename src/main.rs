@@ -1,4 +1,30 @@
-use todos_api::infrastructure::axum_http::http_serve::start;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow, bail};
+use todos_api::{
+    application::usecases::users::UsersUseCase,
+    config::{
+        config_loader,
+        config_model::{Config, DatabaseBackend},
+    },
+    domain::{
+        errors::DomainError,
+        repositories::{audit::AuditRepository, users::UsersRepository},
+        value_objects::users::RegisterUserModel,
+    },
+    infrastructure::{
+        axum_http::http_serve::start,
+        file_mailer::FileMailer,
+        postgres::{
+            self,
+            repositories::{audit::AuditPostgres, users::UsersPostgres},
+        },
+        sqlite::{
+            self,
+            repositories::{audit::AuditSqlite, users::UsersSqlite},
+        },
+    },
+};
 use tracing::info;
 
 #[tokio::main]
@@ -7,7 +33,122 @@ async fn main() {
         .with_max_level(tracing::Level::DEBUG)
         .init();
 
-    info!("Starting the server...");
+    let config = config_loader::load().expect("Failed to load config");
+
+    match std::env::args().nth(1).as_deref() {
+        Some("migrate") => {
+            info!("Running migrations...");
+
+            migrate(&config).await.expect("Failed to run migrations");
+        }
+        Some("create-admin") => {
+            info!("Creating the first admin...");
+
+            create_admin(&config)
+                .await
+                .expect("Failed to create the admin");
+        }
+        _ => {
+            info!("Starting the server...");
+
+            start(Arc::new(config))
+                .await
+                .expect("Failed to start the server");
+        }
+    }
+}
+
+async fn migrate(config: &Config) -> Result<()> {
+    match config.database.backend {
+        DatabaseBackend::Memory => info!("Memory backend has no migrations"),
+        // SQLite migrations are embedded and applied on every connect.
+        DatabaseBackend::Sqlite => {
+            sqlite::connect(&config.database.url).await?;
+        }
+        DatabaseBackend::Postgres => {
+            let pool =
+                postgres::connect(&config.database.url, config.database.max_connections).await?;
+
+            postgres::migrate(&pool).await?;
+        }
+    }
+
+    info!("Migrations are up to date");
+
+    Ok(())
+}
+
+// The username comes from the command line and the password from ADMIN_PASSWORD,
+// so it never shows up in the shell history.
+async fn create_admin(config: &Config) -> Result<()> {
+    let username = std::env::args()
+        .nth(2)
+        .context("Usage: todos-api create-admin <username>")?;
+    let password = std::env::var("ADMIN_PASSWORD").context("ADMIN_PASSWORD must be set")?;
+    let user_model = RegisterUserModel {
+        username,
+        password,
+        email: None,
+    };
+
+    match config.database.backend {
+        DatabaseBackend::Memory => bail!("Memory backend does not persist users"),
+        DatabaseBackend::Sqlite => {
+            let pool = sqlite::connect(&config.database.url).await?;
+
+            bootstrap_admin(
+                config,
+                UsersSqlite::new(pool.clone()),
+                AuditSqlite::new(pool),
+                user_model,
+            )
+            .await?;
+        }
+        DatabaseBackend::Postgres => {
+            let pool =
+                postgres::connect(&config.database.url, config.database.max_connections).await?;
+
+            bootstrap_admin(
+                config,
+                UsersPostgres::new(pool.clone()),
+                AuditPostgres::new(pool),
+                user_model,
+            )
+            .await?;
+        }
+    }
+
+    info!("Admin created");
+
+    Ok(())
+}
+
+async fn bootstrap_admin<T, A>(
+    config: &Config,
+    users_repository: T,
+    audit_repository: A,
+    user_model: RegisterUserModel,
+) -> Result<()>
+where
+    T: UsersRepository + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let users_use_case = UsersUseCase::new(
+        Arc::new(users_repository),
+        Arc::new(audit_repository),
+        Arc::new(FileMailer::new(
+            &config.mailer.outbox_dir,
+            &config.mailer.from,
+        )),
+        Arc::new(config_loader::load_password_policy(config)?),
+        Arc::new(config.clone()),
+    );
 
-    start().await.expect("Failed to start the server");
+    users_use_case
+        .bootstrap_admin(user_model)
+        .await
+        .map_err(|e| match e {
+            DomainError::Validation { message, fields } => anyhow!("{}: {:?}", message, fields),
+            e => e.into(),
+        })
 }
@@ -0,0 +1,194 @@
+mod common;
+
+use serde_json::{Value, json};
+use todos_api::domain::entities::users::Role;
+
+use common::TestServer;
+
+async fn post_refresh_token(
+    server: &TestServer,
+    path: &str,
+    refresh_token: &str,
+) -> reqwest::Response {
+    server
+        .client
+        .post(server.url(path))
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn cookieless_clients_refresh_and_log_out_with_the_token_in_the_body() {
+    let server = TestServer::start().await;
+    server.create_user("alice", Role::User).await;
+    let passport = server.login("alice").await;
+
+    let response = post_refresh_token(
+        &server,
+        "/authentication/refresh-token",
+        &passport.refresh_token,
+    )
+    .await;
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().get("set-cookie").is_none());
+    let body: Value = response.json().await.unwrap();
+    let access_token = body["data"]["access_token"].as_str().unwrap();
+    let refresh_token = body["data"]["refresh_token"].as_str().unwrap();
+    assert_eq!(server.get("/todos", access_token).await.status(), 200);
+
+    let response = post_refresh_token(&server, "/authentication/logout", refresh_token).await;
+    assert_eq!(response.status(), 200);
+
+    let after_logout =
+        post_refresh_token(&server, "/authentication/refresh-token", refresh_token).await;
+    assert_eq!(after_logout.status(), 401);
+}
+
+#[tokio::test]
+async fn cookieless_clients_log_out_everywhere_with_the_token_in_the_body() {
+    let server = TestServer::start().await;
+    server.create_user("alice", Role::User).await;
+    let laptop = server.login("alice").await;
+    let phone = server.login("alice").await;
+
+    let response =
+        post_refresh_token(&server, "/authentication/logout-all", &laptop.refresh_token).await;
+    assert_eq!(response.status(), 200);
+
+    for passport in [laptop, phone] {
+        let response = post_refresh_token(
+            &server,
+            "/authentication/refresh-token",
+            &passport.refresh_token,
+        )
+        .await;
+        assert_eq!(response.status(), 401);
+    }
+}
+
+#[tokio::test]
+async fn refreshing_without_a_token_is_rejected() {
+    let server = TestServer::start().await;
+
+    let response = server
+        .client
+        .post(server.url("/authentication/refresh-token"))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn browsers_still_refresh_with_the_cookie() {
+    let server = TestServer::start().await;
+    server.create_user("alice", Role::User).await;
+    let passport = server.login("alice").await;
+
+    // Clients often send a JSON content type even when the body is empty.
+    let response = server
+        .client
+        .post(server.url("/authentication/refresh-token"))
+        .header(
+            "cookie",
+            format!("rft={}; csrf=csrf-token", passport.refresh_token),
+        )
+        .header("x-csrf-token", "csrf-token")
+        .header("origin", "http://localhost:3000")
+        .header("content-type", "application/json")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert!(
+        response
+            .headers()
+            .get_all("set-cookie")
+            .iter()
+            .any(|cookie| cookie.to_str().unwrap().starts_with("rft="))
+    );
+}
+
+#[tokio::test]
+async fn access_tokens_stop_working_once_the_account_is_deleted() {
+    let server = TestServer::start().await;
+    server.create_user("alice", Role::User).await;
+    let passport = server.login("alice").await;
+
+    let response = server
+        .client
+        .delete(server.url("/users/me"))
+        .bearer_auth(&passport.access_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    assert_eq!(
+        server.get("/todos", &passport.access_token).await.status(),
+        401
+    );
+    let response = server
+        .post(
+            "/todos",
+            &passport.access_token,
+            json!({ "title": "Still here?" }),
+        )
+        .await;
+    assert_eq!(response.status(), 401);
+}
+
+#[tokio::test]
+async fn revoking_a_session_rejects_its_access_token_at_once() {
+    let server = TestServer::start().await;
+    server.create_user("alice", Role::User).await;
+    let laptop = server.login("alice").await;
+    let sessions: Value = server
+        .get("/users/me/sessions", &laptop.access_token)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let laptop_session = sessions["data"][0]["id"].as_str().unwrap().to_string();
+
+    let phone = server.login("alice").await;
+    let sessions: Value = server
+        .get("/users/me/sessions", &laptop.access_token)
+        .await
+        .json()
+        .await
+        .unwrap();
+    let phone_session = sessions["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|session| session["id"].as_str().unwrap())
+        .find(|id| *id != laptop_session)
+        .unwrap();
+    assert_eq!(
+        server.get("/todos", &phone.access_token).await.status(),
+        200
+    );
+
+    let response = server
+        .client
+        .delete(server.url(&format!("/users/me/sessions/{phone_session}")))
+        .bearer_auth(&laptop.access_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    assert_eq!(
+        server.get("/todos", &phone.access_token).await.status(),
+        401
+    );
+    assert_eq!(
+        server.get("/todos", &laptop.access_token).await.status(),
+        200
+    );
+}
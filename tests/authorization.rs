@@ -0,0 +1,65 @@
+mod common;
+
+use serde_json::json;
+use todos_api::domain::entities::users::Role;
+
+use common::TestServer;
+
+#[tokio::test]
+async fn a_demoted_admin_loses_admin_routes_with_the_token_they_already_have() {
+    let server = TestServer::start().await;
+    server.create_user("root", Role::Admin).await;
+    let demoted = server.create_user("alice", Role::Admin).await;
+    let root = server.login("root").await;
+    let alice = server.login("alice").await;
+    assert_eq!(
+        server.get("/users", &alice.access_token).await.status(),
+        200
+    );
+
+    let response = server
+        .client
+        .put(server.url(&format!("/users/{}/role", demoted)))
+        .bearer_auth(&root.access_token)
+        .json(&json!({ "role": "user" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    assert_eq!(
+        server.get("/users", &alice.access_token).await.status(),
+        403
+    );
+    assert_eq!(
+        server.get("/todos", &alice.access_token).await.status(),
+        200
+    );
+}
+
+#[tokio::test]
+async fn a_promoted_user_gets_admin_routes_without_logging_in_again() {
+    let server = TestServer::start().await;
+    server.create_user("root", Role::Admin).await;
+    let promoted = server.create_user("alice", Role::User).await;
+    let root = server.login("root").await;
+    let alice = server.login("alice").await;
+    assert_eq!(
+        server.get("/users", &alice.access_token).await.status(),
+        403
+    );
+
+    server
+        .client
+        .put(server.url(&format!("/users/{}/role", promoted)))
+        .bearer_auth(&root.access_token)
+        .json(&json!({ "role": "admin" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        server.get("/users", &alice.access_token).await.status(),
+        200
+    );
+}
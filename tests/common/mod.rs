@@ -0,0 +1,159 @@
+#![allow(dead_code)]
+
+use std::{net::SocketAddr, sync::Arc};
+
+use reqwest::{Client, Response};
+use serde_json::{Value, json};
+use todos_api::{
+    config::config_model::{Config, Jwt, Mailer},
+    domain::{
+        entities::users::{RegisterUserEntity, Role},
+        repositories::users::UsersRepository,
+    },
+    infrastructure::{
+        app_state::repositories::{
+            audit::AuditAppState, todos::TodosAppState, users::UsersAppState,
+        },
+        argon2_hashing,
+        axum_http::http_serve,
+        file_mailer::FileMailer,
+    },
+};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+pub const PASSWORD: &str = "Str0ng-Pass!word";
+
+// The whole API on the in-memory backend, served on a free port. The client keeps no
+// cookies, so every request is what a cookieless API client would send.
+pub struct TestServer {
+    pub users_repository: Arc<UsersAppState>,
+    pub client: Client,
+    base_url: String,
+}
+
+impl TestServer {
+    pub async fn start() -> Self {
+        Self::with_config(config()).await
+    }
+
+    pub async fn with_config(config: Config) -> Self {
+        let todos_repository = TodosAppState::new();
+        let users_repository = Arc::new(UsersAppState::new(&todos_repository));
+        let mailer = Arc::new(FileMailer::new(
+            &config.mailer.outbox_dir,
+            &config.mailer.from,
+        ));
+
+        let app = http_serve::app(
+            Arc::new(config),
+            Arc::new(todos_repository),
+            Arc::clone(&users_repository),
+            Arc::new(AuditAppState::new()),
+            mailer,
+        )
+        .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+        });
+
+        Self {
+            users_repository,
+            client: Client::new(),
+            base_url,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    // Straight into the repository, so tests can set up admins the API won't create.
+    pub async fn create_user(&self, username: &str, role: Role) -> String {
+        self.users_repository
+            .register(RegisterUserEntity {
+                username: username.to_string(),
+                password_hash: argon2_hashing::hash(PASSWORD).unwrap(),
+                role,
+                email: None,
+                verified: true,
+            })
+            .await
+            .unwrap();
+
+        self.users_repository
+            .find_by_username(username.to_string())
+            .await
+            .unwrap()
+            .unwrap()
+            .id
+    }
+
+    // Logs in with `?response=json` and returns the passport.
+    pub async fn login(&self, username: &str) -> Passport {
+        let response = self
+            .client
+            .post(self.url("/authentication/login?response=json"))
+            .json(&json!({ "username": username, "password": PASSWORD }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let body: Value = response.json().await.unwrap();
+
+        Passport {
+            access_token: body["data"]["access_token"].as_str().unwrap().to_string(),
+            refresh_token: body["data"]["refresh_token"].as_str().unwrap().to_string(),
+        }
+    }
+
+    pub async fn get(&self, path: &str, access_token: &str) -> Response {
+        self.client
+            .get(self.url(path))
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    pub async fn post(&self, path: &str, access_token: &str, body: Value) -> Response {
+        self.client
+            .post(self.url(path))
+            .bearer_auth(access_token)
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+    }
+}
+
+pub struct Passport {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+pub fn config() -> Config {
+    Config {
+        jwt: Jwt {
+            access_secret: "access-secret".to_string(),
+            refresh_secret: "refresh-secret".to_string(),
+            ..Default::default()
+        },
+        mailer: Mailer {
+            outbox_dir: std::env::temp_dir()
+                .join(format!("todos-api-outbox-{}", Uuid::new_v4()))
+                .to_string_lossy()
+                .into_owned(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
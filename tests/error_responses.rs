@@ -0,0 +1,81 @@
+mod common;
+
+use serde_json::{Value, json};
+use todos_api::domain::entities::users::Role;
+
+use common::TestServer;
+
+async fn error_body(response: reqwest::Response) -> Value {
+    assert!(
+        response
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("application/json")),
+        "expected a JSON error body"
+    );
+
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn malformed_json_bodies_get_the_json_error_body() {
+    let server = TestServer::start().await;
+
+    let response = server
+        .client
+        .post(server.url("/authentication/login"))
+        .header("content-type", "application/json")
+        .body("{\"username\": ")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+    let body = error_body(response).await;
+    assert_eq!(body["code"], "validation");
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+async fn json_bodies_with_the_wrong_shape_get_the_json_error_body() {
+    let server = TestServer::start().await;
+    server.create_user("alice", Role::User).await;
+    let passport = server.login("alice").await;
+
+    let response = server
+        .post("/todos", &passport.access_token, json!({ "title": 42 }))
+        .await;
+
+    assert_eq!(response.status(), 400);
+    assert_eq!(error_body(response).await["code"], "validation");
+}
+
+#[tokio::test]
+async fn malformed_query_strings_get_the_json_error_body() {
+    let server = TestServer::start().await;
+    server.create_user("alice", Role::User).await;
+    let passport = server.login("alice").await;
+
+    let response = server
+        .get("/todos?completed=maybe", &passport.access_token)
+        .await;
+
+    assert_eq!(response.status(), 400);
+    assert_eq!(error_body(response).await["code"], "validation");
+}
+
+#[tokio::test]
+async fn unknown_routes_get_the_json_error_body() {
+    let server = TestServer::start().await;
+
+    let response = server
+        .client
+        .get(server.url("/no-such-route"))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 404);
+    assert_eq!(error_body(response).await["code"], "not_found");
+}
@@ -0,0 +1,576 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use axum::{
+    Form, Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{EncodingKey, Header};
+use reqwest::Url;
+use serde_json::{Value, json};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use todos_api::{
+    application::usecases::authentication::AuthenticationUseCase,
+    config::config_model::{Config, Jwt, JwtAlgorithm, JwtKey, Oidc, OidcProvider},
+    domain::{
+        entities::{
+            user_identities::UserIdentityEntity,
+            users::{RegisterUserEntity, Role, UserEntity},
+        },
+        errors::DomainError,
+        repositories::users::UsersRepository,
+    },
+    infrastructure::{
+        app_state::repositories::{
+            audit::AuditAppState, todos::TodosAppState, users::UsersAppState,
+        },
+        jwt_authentication::{
+            authentication_model::{DeviceModel, LoginOutcome, OidcCallbackModel, TotpLoginModel},
+            jwt_keys::JwtKeys,
+        },
+        login_throttle::LoginThrottle,
+        totp,
+    },
+};
+use tokio::net::TcpListener;
+
+const CLIENT_ID: &str = "todos-api";
+const PROVIDER: &str = "mock";
+
+// Authorization codes the provider has handed out, with what the token endpoint answers.
+struct Grant {
+    code_challenge: String,
+    id_token: String,
+}
+
+struct ProviderState {
+    issuer: String,
+    keys: JwtKeys,
+    grants: Mutex<HashMap<String, Grant>>,
+    discovery_requests: AtomicUsize,
+    jwks_requests: AtomicUsize,
+}
+
+async fn discovery(State(provider): State<Arc<ProviderState>>) -> Json<Value> {
+    provider.discovery_requests.fetch_add(1, Ordering::SeqCst);
+
+    Json(json!({
+        "issuer": provider.issuer,
+        "authorization_endpoint": format!("{}/authorize", provider.issuer),
+        "token_endpoint": format!("{}/token", provider.issuer),
+        "jwks_uri": format!("{}/jwks", provider.issuer),
+    }))
+}
+
+async fn jwks(State(provider): State<Arc<ProviderState>>) -> impl IntoResponse {
+    provider.jwks_requests.fetch_add(1, Ordering::SeqCst);
+
+    Json(provider.keys.jwks().clone())
+}
+
+// Refuses the code unless the verifier hashes to the challenge sent with the authorization
+// request, as a real provider does for PKCE.
+async fn token(
+    State(provider): State<Arc<ProviderState>>,
+    Form(form): Form<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let grant = form
+        .get("code")
+        .and_then(|code| provider.grants.lock().unwrap().remove(code));
+    let verifier_matches = |grant: &Grant| {
+        form.get("code_verifier").is_some_and(|verifier| {
+            URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())) == grant.code_challenge
+        })
+    };
+
+    match grant {
+        Some(grant)
+            if verifier_matches(&grant)
+                && form.get("client_id").map(String::as_str) == Some(CLIENT_ID) =>
+        {
+            Json(json!({ "id_token": grant.id_token, "token_type": "Bearer" })).into_response()
+        }
+        _ => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "invalid_grant" })),
+        )
+            .into_response(),
+    }
+}
+
+struct Fixture {
+    provider: Arc<ProviderState>,
+    users_repository: Arc<UsersAppState>,
+    authentication: AuthenticationUseCase<UsersAppState, AuditAppState>,
+}
+
+// What the test lets the provider put in, or do to, the ID token for one login.
+#[derive(Default)]
+struct IdToken {
+    subject: &'static str,
+    email: Option<&'static str>,
+    nonce: Option<&'static str>,
+    signed_with_secret: bool,
+}
+
+#[derive(Default)]
+struct Callback {
+    wrong_state: bool,
+    wrong_code_verifier: bool,
+}
+
+impl Fixture {
+    async fn new(auto_provision: bool, link_by_email: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+        let keys = JwtKeys::load(&Jwt {
+            algorithm: JwtAlgorithm::Rs256,
+            issuer: issuer.clone(),
+            audience: CLIENT_ID.to_string(),
+            signing_kid: "provider-key".to_string(),
+            keys: vec![JwtKey {
+                kid: "provider-key".to_string(),
+                public_key_path: format!("{}/oidc_provider_public.pem", fixtures),
+                private_key_path: Some(format!("{}/oidc_provider_private.pem", fixtures)),
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let provider = Arc::new(ProviderState {
+            issuer: issuer.clone(),
+            keys,
+            grants: Mutex::new(HashMap::new()),
+            discovery_requests: AtomicUsize::new(0),
+            jwks_requests: AtomicUsize::new(0),
+        });
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(Arc::clone(&provider));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let config = Arc::new(Config {
+            jwt: Jwt {
+                access_secret: "access-secret".to_string(),
+                refresh_secret: "refresh-secret".to_string(),
+                ..Default::default()
+            },
+            oidc: Oidc {
+                providers: vec![OidcProvider {
+                    name: PROVIDER.to_string(),
+                    issuer,
+                    client_id: CLIENT_ID.to_string(),
+                    client_secret: String::new(),
+                    redirect_url: "http://localhost:3001/authentication/oidc/mock/callback"
+                        .to_string(),
+                    scopes: vec!["openid".to_string(), "email".to_string()],
+                    auto_provision,
+                    link_by_email,
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let users_repository = Arc::new(UsersAppState::new(&TodosAppState::new()));
+        let authentication = AuthenticationUseCase::new(
+            Arc::clone(&users_repository),
+            Arc::new(AuditAppState::new()),
+            Arc::new(JwtKeys::load(&config.jwt).unwrap()),
+            Arc::new(LoginThrottle::new(config.login_throttle.clone())),
+            config,
+        );
+
+        Self {
+            provider,
+            users_repository,
+            authentication,
+        }
+    }
+
+    // Runs one login from the redirect to the provider through its callback.
+    async fn login(
+        &self,
+        id_token: IdToken,
+        callback: Callback,
+    ) -> Result<LoginOutcome, DomainError> {
+        let authorization = self
+            .authentication
+            .start_oidc_login(PROVIDER.to_string())
+            .await?;
+        let url = Url::parse(&authorization.authorization_url).unwrap();
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+                .unwrap()
+        };
+
+        let code = format!("code-{}", Utc::now().timestamp_nanos_opt().unwrap());
+        let code_challenge = match callback.wrong_code_verifier {
+            true => URL_SAFE_NO_PAD.encode(Sha256::digest(b"some other verifier")),
+            false => param("code_challenge"),
+        };
+        let nonce = id_token
+            .nonce
+            .map(str::to_string)
+            .unwrap_or_else(|| param("nonce"));
+        self.provider.grants.lock().unwrap().insert(
+            code.clone(),
+            Grant {
+                code_challenge,
+                id_token: self.sign(&id_token, &nonce),
+            },
+        );
+
+        let state = match callback.wrong_state {
+            true => "forged".to_string(),
+            false => param("state"),
+        };
+
+        self.authentication
+            .login_oidc(
+                PROVIDER.to_string(),
+                OidcCallbackModel {
+                    code: Some(code),
+                    state: Some(state),
+                    error: None,
+                },
+                Some(authorization.state_token),
+                device(),
+            )
+            .await
+    }
+
+    fn sign(&self, id_token: &IdToken, nonce: &str) -> String {
+        let now = Utc::now().timestamp();
+        let claims = json!({
+            "iss": self.provider.issuer,
+            "aud": CLIENT_ID,
+            "sub": id_token.subject,
+            "exp": now + 300,
+            "iat": now,
+            "nonce": nonce,
+            "email": id_token.email,
+            "email_verified": id_token.email.is_some(),
+            "preferred_username": id_token.subject,
+        });
+
+        match id_token.signed_with_secret {
+            // HS256 keyed with public material is the classic algorithm confusion attack.
+            true => jsonwebtoken::encode(
+                &Header::default(),
+                &claims,
+                &EncodingKey::from_secret(b"public-key-material"),
+            )
+            .unwrap(),
+            false => self.provider.keys.generate_token(&claims).unwrap(),
+        }
+    }
+
+    async fn register(&self, username: &str, email: &str) -> UserEntity {
+        self.users_repository
+            .register(RegisterUserEntity {
+                username: username.to_string(),
+                password_hash: String::new(),
+                role: Role::User,
+                email: Some(email.to_string()),
+                verified: true,
+            })
+            .await
+            .unwrap();
+
+        self.users_repository
+            .find_by_username(username.to_string())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    async fn linked_user(&self, subject: &str) -> Option<UserEntity> {
+        self.users_repository
+            .find_by_identity(self.provider.issuer.clone(), subject.to_string())
+            .await
+            .unwrap()
+    }
+}
+
+fn device() -> DeviceModel {
+    DeviceModel {
+        ip_address: "127.0.0.1".to_string(),
+        user_agent: None,
+    }
+}
+
+fn current_totp_code(secret: &str) -> String {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).unwrap();
+    let mut mac = Hmac::<Sha1>::new_from_slice(&key).unwrap();
+    mac.update(&((Utc::now().timestamp() / 30) as u64).to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    format!("{:06}", binary % 1_000_000)
+}
+
+fn subject_of(access_token: &str) -> String {
+    let payload = access_token.split('.').nth(1).unwrap();
+    let claims: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).unwrap()).unwrap();
+
+    claims["sub"].as_str().unwrap().to_string()
+}
+
+fn assert_unauthorized(outcome: Result<LoginOutcome, DomainError>) {
+    assert!(
+        matches!(outcome, Err(DomainError::Unauthorized(_))),
+        "expected the login to be refused, got {:?}",
+        outcome.map(|_| ())
+    );
+}
+
+#[tokio::test]
+async fn provisions_a_user_after_discovery_jwks_and_pkce_exchange() {
+    let fixture = Fixture::new(true, false).await;
+
+    let outcome = fixture
+        .login(
+            IdToken {
+                subject: "new-user",
+                email: Some("new@example.com"),
+                ..Default::default()
+            },
+            Callback::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(outcome, LoginOutcome::Passport(_)));
+    assert_eq!(
+        fixture.provider.discovery_requests.load(Ordering::SeqCst),
+        1
+    );
+    assert_eq!(fixture.provider.jwks_requests.load(Ordering::SeqCst), 1);
+
+    let user = fixture.linked_user("new-user").await.unwrap();
+    assert_eq!(user.username, "new-user");
+    assert_eq!(user.email.as_deref(), Some("new@example.com"));
+}
+
+#[tokio::test]
+async fn signs_in_the_user_linked_by_issuer_and_subject() {
+    // Neither provisioning nor email linking is on, so only the linked identity can match.
+    let fixture = Fixture::new(false, false).await;
+    let user = fixture.register("alice", "alice@example.com").await;
+    fixture
+        .users_repository
+        .add_identity(UserIdentityEntity {
+            issuer: fixture.provider.issuer.clone(),
+            subject: "alice-at-provider".to_string(),
+            user_id: user.id.clone(),
+            created_at: Utc::now().naive_utc(),
+        })
+        .await
+        .unwrap();
+
+    let outcome = fixture
+        .login(
+            IdToken {
+                subject: "alice-at-provider",
+                email: Some("someone-else@example.com"),
+                ..Default::default()
+            },
+            Callback::default(),
+        )
+        .await
+        .unwrap();
+    let LoginOutcome::Passport(passport) = outcome else {
+        panic!("expected a passport");
+    };
+    assert_eq!(subject_of(&passport.access_token), user.id);
+
+    let unknown = fixture
+        .login(
+            IdToken {
+                subject: "stranger",
+                ..Default::default()
+            },
+            Callback::default(),
+        )
+        .await;
+    assert!(matches!(unknown, Err(DomainError::Forbidden(_))));
+    assert_eq!(fixture.users_repository.list().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn links_an_existing_user_by_verified_email() {
+    let fixture = Fixture::new(true, true).await;
+    let user = fixture.register("alice", "alice@example.com").await;
+
+    let outcome = fixture
+        .login(
+            IdToken {
+                subject: "alice-at-provider",
+                email: Some("alice@example.com"),
+                ..Default::default()
+            },
+            Callback::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(outcome, LoginOutcome::Passport(_)));
+    assert_eq!(
+        fixture.linked_user("alice-at-provider").await.unwrap().id,
+        user.id
+    );
+    assert_eq!(fixture.users_repository.list().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn email_linking_asks_for_the_second_factor_first() {
+    let fixture = Fixture::new(true, true).await;
+    let user = fixture.register("alice", "alice@example.com").await;
+    let secret = totp::generate_secret();
+    fixture
+        .users_repository
+        .update_totp(user.id.clone(), Some(secret.clone()), true)
+        .await
+        .unwrap();
+
+    let outcome = fixture
+        .login(
+            IdToken {
+                subject: "alice-at-provider",
+                email: Some("alice@example.com"),
+                ..Default::default()
+            },
+            Callback::default(),
+        )
+        .await
+        .unwrap();
+
+    let LoginOutcome::TotpRequired(challenge) = outcome else {
+        panic!("expected a TOTP challenge");
+    };
+    assert!(fixture.linked_user("alice-at-provider").await.is_none());
+
+    fixture
+        .authentication
+        .login_totp(
+            TotpLoginModel {
+                challenge_token: challenge.challenge_token,
+                code: current_totp_code(&secret),
+            },
+            device(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fixture.linked_user("alice-at-provider").await.unwrap().id,
+        user.id
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_code_exchanged_without_the_pkce_verifier() {
+    let fixture = Fixture::new(true, false).await;
+
+    assert_unauthorized(
+        fixture
+            .login(
+                IdToken {
+                    subject: "new-user",
+                    ..Default::default()
+                },
+                Callback {
+                    wrong_code_verifier: true,
+                    ..Default::default()
+                },
+            )
+            .await,
+    );
+    assert!(fixture.linked_user("new-user").await.is_none());
+}
+
+#[tokio::test]
+async fn rejects_a_mismatched_state() {
+    let fixture = Fixture::new(true, false).await;
+
+    assert_unauthorized(
+        fixture
+            .login(
+                IdToken {
+                    subject: "new-user",
+                    ..Default::default()
+                },
+                Callback {
+                    wrong_state: true,
+                    ..Default::default()
+                },
+            )
+            .await,
+    );
+    assert!(fixture.linked_user("new-user").await.is_none());
+}
+
+#[tokio::test]
+async fn rejects_a_mismatched_nonce() {
+    let fixture = Fixture::new(true, false).await;
+
+    assert_unauthorized(
+        fixture
+            .login(
+                IdToken {
+                    subject: "new-user",
+                    nonce: Some("replayed-nonce"),
+                    ..Default::default()
+                },
+                Callback::default(),
+            )
+            .await,
+    );
+    assert!(fixture.linked_user("new-user").await.is_none());
+}
+
+#[tokio::test]
+async fn rejects_a_symmetrically_signed_id_token() {
+    let fixture = Fixture::new(true, false).await;
+
+    assert_unauthorized(
+        fixture
+            .login(
+                IdToken {
+                    subject: "new-user",
+                    signed_with_secret: true,
+                    ..Default::default()
+                },
+                Callback::default(),
+            )
+            .await,
+    );
+    assert!(fixture.linked_user("new-user").await.is_none());
+}
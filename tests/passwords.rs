@@ -0,0 +1,40 @@
+mod common;
+
+use serde_json::json;
+use todos_api::{config::config_model::LoginThrottle, domain::entities::users::Role};
+
+use common::{PASSWORD, TestServer};
+
+#[tokio::test]
+async fn wrong_current_passwords_lock_the_password_change() {
+    let mut config = common::config();
+    config.login_throttle = LoginThrottle {
+        free_attempts: 1,
+        lockout_attempts: 2,
+        lockout_secs: 900,
+        ..Default::default()
+    };
+    let server = TestServer::with_config(config).await;
+    server.create_user("alice", Role::User).await;
+    let passport = server.login("alice").await;
+
+    let change_password = |current_password: &str| {
+        server.post(
+            "/users/me/password",
+            &passport.access_token,
+            json!({
+                "current_password": current_password,
+                "new_password": "An0ther-Str0ng-Pass!",
+            }),
+        )
+    };
+
+    for _ in 0..2 {
+        assert_eq!(change_password("wrong-password").await.status(), 400);
+    }
+
+    // Locked now, so even the right password is turned away.
+    let response = change_password(PASSWORD).await;
+    assert_eq!(response.status(), 429);
+    assert!(response.headers().get("retry-after").is_some());
+}
@@ -0,0 +1,812 @@
+use chrono::{DateTime, Duration, FixedOffset, TimeZone, Utc};
+use serde_json::json;
+use todos_api::{
+    domain::{
+        entities::{
+            audit_events::{AddAuditEventEntity, AuditEventKind, AuditQueryEntity},
+            refresh_tokens::RefreshTokenEntity,
+            todos::{
+                AddTodoEntity, SortDirection, TodoCursor, TodoEntity, TodoQueryEntity,
+                TodoSortField, UpdateTodoEntity,
+            },
+            users::{RegisterUserEntity, Role, UpdateUserEntity},
+        },
+        errors::DomainError,
+        repositories::{audit::AuditRepository, todos::TodosRepository, users::UsersRepository},
+    },
+    infrastructure::{
+        app_state::repositories::{
+            audit::AuditAppState, todos::TodosAppState, users::UsersAppState,
+        },
+        postgres::{
+            connect as connect_postgres, migrate as migrate_postgres,
+            repositories::{audit::AuditPostgres, todos::TodosPostgres, users::UsersPostgres},
+        },
+        sqlite::{
+            connect as connect_sqlite,
+            repositories::{audit::AuditSqlite, todos::TodosSqlite, users::UsersSqlite},
+        },
+    },
+};
+use uuid::Uuid;
+
+// Every backend runs the same behaviours, so one that drifts from the others fails here
+// rather than only once it is deployed.
+macro_rules! repository_suite {
+    ($backend:ident, $repositories:ident $(, #[$attribute:meta])*) => {
+        mod $backend {
+            repository_suite!(
+                @behaviours $repositories, [$(#[$attribute])*];
+                todos_are_scoped_to_their_owner,
+                missing_todos_are_not_found,
+                usernames_are_unique_ignoring_case,
+                refresh_tokens_rotate_once,
+                revoked_refresh_token_families_cannot_rotate,
+                query_filters_by_completion_and_creation_time,
+                query_sorts_and_pages_by_every_key,
+                query_searches_titles_ignoring_ascii_case_only,
+                query_sorts_and_pages_by_due_date_with_undated_last,
+                query_filters_by_due_date_and_overdue,
+                due_dates_are_stored_as_utc_instants
+            );
+            repository_suite!(
+                @audit_behaviours $repositories, [$(#[$attribute])*];
+                audit_events_are_listed_newest_first,
+                audit_events_filter_by_kind_and_time
+            );
+        }
+    };
+    (@behaviours $repositories:ident, $attributes:tt; $($behaviour:ident),*) => {
+        $(repository_suite!(@test $repositories, $attributes, $behaviour);)*
+    };
+    (@audit_behaviours $repositories:ident, $attributes:tt; $($behaviour:ident),*) => {
+        $(repository_suite!(@audit_test $repositories, $attributes, $behaviour);)*
+    };
+    (@test $repositories:ident, [$(#[$attribute:meta])*], $behaviour:ident) => {
+        #[tokio::test]
+        $(#[$attribute])*
+        async fn $behaviour() {
+            let (todos, users, _) = super::$repositories().await;
+
+            super::$behaviour(&todos, &users).await;
+        }
+    };
+    (@audit_test $repositories:ident, [$(#[$attribute:meta])*], $behaviour:ident) => {
+        #[tokio::test]
+        $(#[$attribute])*
+        async fn $behaviour() {
+            let (_, _, audit) = super::$repositories().await;
+
+            super::$behaviour(&audit).await;
+        }
+    };
+}
+
+async fn app_state_repositories() -> (TodosAppState, UsersAppState, AuditAppState) {
+    let todos = TodosAppState::new();
+    let users = UsersAppState::new(&todos);
+
+    (todos, users, AuditAppState::new())
+}
+
+async fn sqlite_repositories() -> (TodosSqlite, UsersSqlite, AuditSqlite) {
+    let pool = connect_sqlite("sqlite::memory:").await.unwrap();
+
+    (
+        TodosSqlite::new(pool.clone()),
+        UsersSqlite::new(pool.clone()),
+        AuditSqlite::new(pool),
+    )
+}
+
+async fn postgres_repositories() -> (TodosPostgres, UsersPostgres, AuditPostgres) {
+    let url = std::env::var("DATABASE_URL")
+        .expect("DATABASE_URL must point at a PostgreSQL database to run these tests");
+    let pool = connect_postgres(&url, 5).await.unwrap();
+    migrate_postgres(&pool).await.unwrap();
+
+    (
+        TodosPostgres::new(pool.clone()),
+        UsersPostgres::new(pool.clone()),
+        AuditPostgres::new(pool),
+    )
+}
+
+repository_suite!(app_state, app_state_repositories);
+repository_suite!(sqlite, sqlite_repositories);
+// Needs a database to write to, so it only runs on request: `cargo test -- --ignored`.
+repository_suite!(
+    postgres,
+    postgres_repositories,
+    #[ignore = "needs DATABASE_URL pointing at a PostgreSQL database"]
+);
+
+// Usernames get a random suffix so backends that share a database between tests don't
+// collide.
+async fn register(users: &impl UsersRepository, name: &str) -> (String, String) {
+    let username = format!("{}-{}", name, Uuid::new_v4().simple());
+
+    users
+        .register(RegisterUserEntity {
+            username: username.clone(),
+            password_hash: String::new(),
+            role: Role::User,
+            email: None,
+            verified: true,
+        })
+        .await
+        .unwrap();
+    let user = users
+        .find_by_username(username.clone())
+        .await
+        .unwrap()
+        .unwrap();
+
+    (user.id, username)
+}
+
+async fn add_todo(todos: &impl TodosRepository, user_id: &str, title: &str) -> String {
+    add_todo_due_at(todos, user_id, title, None).await
+}
+
+async fn add_todo_due_at(
+    todos: &impl TodosRepository,
+    user_id: &str,
+    title: &str,
+    due_at: Option<DateTime<Utc>>,
+) -> String {
+    todos
+        .add(
+            user_id.to_string(),
+            AddTodoEntity {
+                title: title.to_string(),
+                due_at,
+            },
+        )
+        .await
+        .unwrap()
+        .id
+}
+
+fn refresh_token(user_id: &str, family_id: &str) -> RefreshTokenEntity {
+    let now = Utc::now().naive_utc();
+
+    RefreshTokenEntity {
+        jti: Uuid::new_v4().to_string(),
+        family_id: family_id.to_string(),
+        user_id: user_id.to_string(),
+        expires_at: now + Duration::days(1),
+        created_at: now,
+        rotated_at: None,
+        revoked_at: None,
+    }
+}
+
+fn titles(todos: &[TodoEntity]) -> Vec<&str> {
+    todos.iter().map(|t| t.title.as_str()).collect()
+}
+
+// Reads every page the way `TodosUseCase::list` does, continuing after the last row of
+// the previous one.
+async fn walk(
+    todos: &impl TodosRepository,
+    user_id: &str,
+    sort: TodoSortField,
+    direction: SortDirection,
+) -> Vec<TodoEntity> {
+    let mut result: Vec<TodoEntity> = Vec::new();
+
+    loop {
+        let page = todos
+            .query(
+                user_id.to_string(),
+                TodoQueryEntity {
+                    sort,
+                    direction,
+                    cursor: result.last().map(|todo| TodoCursor {
+                        key: sort.key_of(todo),
+                        id: todo.id.clone(),
+                    }),
+                    limit: 2,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        if page.is_empty() {
+            return result;
+        }
+        result.extend(page);
+    }
+}
+
+fn assert_not_found<T>(result: Result<T, DomainError>) {
+    assert!(
+        matches!(result, Err(DomainError::NotFound(_))),
+        "expected not found, got {:?}",
+        result.map(|_| ()).err()
+    );
+}
+
+fn assert_conflict<T>(result: Result<T, DomainError>) {
+    assert!(
+        matches!(result, Err(DomainError::Conflict(_))),
+        "expected a conflict, got {:?}",
+        result.map(|_| ()).err()
+    );
+}
+
+async fn todos_are_scoped_to_their_owner(
+    todos: &impl TodosRepository,
+    users: &impl UsersRepository,
+) {
+    let (alice, _) = register(users, "alice").await;
+    let (bob, _) = register(users, "bob").await;
+    add_todo(todos, &alice, "alice's").await;
+    let bobs = add_todo(todos, &bob, "bob's").await;
+
+    let listed = todos.list(alice.clone()).await.unwrap();
+    assert_eq!(
+        listed.iter().map(|t| t.title.as_str()).collect::<Vec<_>>(),
+        ["alice's"]
+    );
+    let queried = todos
+        .query(
+            alice.clone(),
+            TodoQueryEntity {
+                limit: 100,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        queried.iter().map(|t| t.title.as_str()).collect::<Vec<_>>(),
+        ["alice's"]
+    );
+
+    assert_not_found(todos.get(alice.clone(), bobs.clone()).await);
+    assert_not_found(
+        todos
+            .update(
+                alice.clone(),
+                bobs.clone(),
+                UpdateTodoEntity {
+                    title: Some("taken over".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await,
+    );
+    assert_not_found(todos.to_completed(alice.clone(), bobs.clone()).await);
+    assert_not_found(todos.delete(alice, bobs.clone()).await);
+
+    let untouched = todos.get(bob, bobs).await.unwrap();
+    assert_eq!(untouched.title, "bob's");
+    assert!(!untouched.completed);
+}
+
+async fn missing_todos_are_not_found(todos: &impl TodosRepository, users: &impl UsersRepository) {
+    let (alice, _) = register(users, "alice").await;
+    let missing = Uuid::new_v4().to_string();
+
+    assert_not_found(todos.get(alice.clone(), missing.clone()).await);
+    assert_not_found(
+        todos
+            .update(alice.clone(), missing.clone(), UpdateTodoEntity::default())
+            .await,
+    );
+    assert_not_found(todos.to_completed(alice.clone(), missing.clone()).await);
+    assert_not_found(todos.delete(alice.clone(), missing).await);
+
+    let deleted = add_todo(todos, &alice, "short-lived").await;
+    todos.delete(alice.clone(), deleted.clone()).await.unwrap();
+    assert_not_found(todos.get(alice.clone(), deleted.clone()).await);
+    assert_not_found(todos.delete(alice, deleted).await);
+}
+
+async fn usernames_are_unique_ignoring_case(
+    _todos: &impl TodosRepository,
+    users: &impl UsersRepository,
+) {
+    let (_, carol) = register(users, "carol").await;
+    let (dave, _) = register(users, "dave").await;
+
+    for username in [carol.clone(), carol.to_uppercase()] {
+        assert_conflict(
+            users
+                .register(RegisterUserEntity {
+                    username,
+                    password_hash: String::new(),
+                    role: Role::User,
+                    email: None,
+                    verified: true,
+                })
+                .await,
+        );
+    }
+
+    assert_conflict(
+        users
+            .update(
+                dave,
+                UpdateUserEntity {
+                    username: Some(carol.to_uppercase()),
+                    display_name: None,
+                    timezone: None,
+                    locale: None,
+                },
+            )
+            .await,
+    );
+}
+
+async fn refresh_tokens_rotate_once(_todos: &impl TodosRepository, users: &impl UsersRepository) {
+    let (alice, _) = register(users, "alice").await;
+    let family = Uuid::new_v4().to_string();
+    let first = refresh_token(&alice, &family);
+    let second = refresh_token(&alice, &family);
+    let replayed = refresh_token(&alice, &family);
+    users.add_refresh_token(first.clone()).await.unwrap();
+
+    assert!(
+        users
+            .rotate_refresh_token(first.jti.clone(), second.clone())
+            .await
+            .unwrap()
+    );
+    // Presenting the old token again must not mint another one.
+    assert!(
+        !users
+            .rotate_refresh_token(first.jti.clone(), replayed.clone())
+            .await
+            .unwrap()
+    );
+
+    let rotated = users.find_refresh_token(first.jti).await.unwrap().unwrap();
+    assert!(rotated.rotated_at.is_some());
+    let current = users.find_refresh_token(second.jti).await.unwrap().unwrap();
+    assert_eq!(current.family_id, family);
+    assert!(current.rotated_at.is_none() && current.revoked_at.is_none());
+    // The family is one session, which rotating keeps alive.
+    assert!(users.is_session_active(family).await.unwrap());
+    assert!(
+        users
+            .find_refresh_token(replayed.jti)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+async fn revoked_refresh_token_families_cannot_rotate(
+    _todos: &impl TodosRepository,
+    users: &impl UsersRepository,
+) {
+    let (alice, _) = register(users, "alice").await;
+    let family = Uuid::new_v4().to_string();
+    let other_family = Uuid::new_v4().to_string();
+    let revoked = refresh_token(&alice, &family);
+    let kept = refresh_token(&alice, &other_family);
+    users.add_refresh_token(revoked.clone()).await.unwrap();
+    users.add_refresh_token(kept.clone()).await.unwrap();
+
+    users
+        .revoke_refresh_token_family(family.clone())
+        .await
+        .unwrap();
+
+    assert!(
+        !users
+            .rotate_refresh_token(revoked.jti.clone(), refresh_token(&alice, &family))
+            .await
+            .unwrap()
+    );
+    let revoked = users
+        .find_refresh_token(revoked.jti)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(revoked.revoked_at.is_some());
+    assert!(!users.is_session_active(family).await.unwrap());
+    assert!(users.is_session_active(other_family.clone()).await.unwrap());
+    assert!(
+        users
+            .rotate_refresh_token(kept.jti, refresh_token(&alice, &other_family))
+            .await
+            .unwrap()
+    );
+}
+
+async fn query_filters_by_completion_and_creation_time(
+    todos: &impl TodosRepository,
+    users: &impl UsersRepository,
+) {
+    let (alice, _) = register(users, "alice").await;
+    add_todo(todos, &alice, "first").await;
+    let second = add_todo(todos, &alice, "second").await;
+    add_todo(todos, &alice, "third").await;
+    todos
+        .to_completed(alice.clone(), second.clone())
+        .await
+        .unwrap();
+    let created_at = todos.get(alice.clone(), second).await.unwrap().created_at;
+
+    let query = |query: TodoQueryEntity| {
+        todos.query(
+            alice.clone(),
+            TodoQueryEntity {
+                limit: 100,
+                ..query
+            },
+        )
+    };
+
+    let completed = query(TodoQueryEntity {
+        completed: Some(true),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(titles(&completed), ["second"]);
+    let incomplete = query(TodoQueryEntity {
+        completed: Some(false),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(titles(&incomplete), ["first", "third"]);
+
+    // Both bounds are exclusive.
+    let after = query(TodoQueryEntity {
+        created_after: Some(created_at),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(titles(&after), ["third"]);
+    let before = query(TodoQueryEntity {
+        created_before: Some(created_at),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(titles(&before), ["first"]);
+}
+
+async fn query_sorts_and_pages_by_every_key(
+    todos: &impl TodosRepository,
+    users: &impl UsersRepository,
+) {
+    let (alice, _) = register(users, "alice").await;
+    // Repeated titles make pages break inside a run of equal keys, where only the id
+    // decides what comes next.
+    let mut ids = Vec::new();
+    for title in ["banana", "apple", "cherry", "apple", "banana"] {
+        ids.push(add_todo(todos, &alice, title).await);
+    }
+    todos
+        .update(
+            alice.clone(),
+            ids[1].clone(),
+            UpdateTodoEntity {
+                completed: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let all = todos.list(alice.clone()).await.unwrap();
+    for sort in [
+        TodoSortField::CreatedAt,
+        TodoSortField::UpdatedAt,
+        TodoSortField::Title,
+    ] {
+        let mut expected = all.clone();
+        expected.sort_by(|a, b| sort.key_of(a).cmp(&sort.key_of(b)).then(a.id.cmp(&b.id)));
+        let expected: Vec<_> = expected.into_iter().map(|t| t.id).collect();
+
+        let ascending = walk(todos, &alice, sort, SortDirection::Asc).await;
+        assert_eq!(
+            ascending.into_iter().map(|t| t.id).collect::<Vec<_>>(),
+            expected,
+            "{:?} ascending",
+            sort
+        );
+
+        let descending = walk(todos, &alice, sort, SortDirection::Desc).await;
+        assert_eq!(
+            descending
+                .into_iter()
+                .rev()
+                .map(|t| t.id)
+                .collect::<Vec<_>>(),
+            expected,
+            "{:?} descending",
+            sort
+        );
+    }
+
+    // The update moved the second todo to the end of the updated_at order.
+    let by_update = walk(todos, &alice, TodoSortField::UpdatedAt, SortDirection::Asc).await;
+    assert_eq!(by_update.last().unwrap().id, ids[1]);
+}
+
+async fn query_searches_titles_ignoring_ascii_case_only(
+    todos: &impl TodosRepository,
+    users: &impl UsersRepository,
+) {
+    let (alice, _) = register(users, "alice").await;
+    for title in [
+        "Émile",
+        "émile",
+        "STRASSE",
+        "Straße",
+        "100% done",
+        "1000 done",
+    ] {
+        add_todo(todos, &alice, title).await;
+    }
+
+    let search = |q: &str| {
+        todos.query(
+            alice.clone(),
+            TodoQueryEntity {
+                q: Some(q.to_string()),
+                limit: 100,
+                ..Default::default()
+            },
+        )
+    };
+
+    assert_eq!(titles(&search("MIL").await.unwrap()), ["Émile", "émile"]);
+    assert_eq!(titles(&search("émile").await.unwrap()), ["émile"]);
+    assert_eq!(titles(&search("ÉMILE").await.unwrap()), ["Émile"]);
+    assert_eq!(titles(&search("strasse").await.unwrap()), ["STRASSE"]);
+    assert_eq!(titles(&search("STRAßE").await.unwrap()), ["Straße"]);
+    // Wildcards in the search are taken literally.
+    assert_eq!(titles(&search("100%").await.unwrap()), ["100% done"]);
+    assert_eq!(titles(&search("0_d").await.unwrap()), Vec::<&str>::new());
+}
+
+async fn query_sorts_and_pages_by_due_date_with_undated_last(
+    todos: &impl TodosRepository,
+    users: &impl UsersRepository,
+) {
+    let (alice, _) = register(users, "alice").await;
+    let noon = Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+    // Three undated todos, so pages of two continue from a cursor without a due date in
+    // both directions, and a repeated due date, so a page breaks inside equal keys.
+    for (title, due_at) in [
+        ("undated", None),
+        ("later", Some(noon + Duration::hours(1))),
+        ("undated", None),
+        ("noon", Some(noon)),
+        ("earlier", Some(noon - Duration::hours(1))),
+        ("undated", None),
+        ("noon", Some(noon)),
+    ] {
+        add_todo_due_at(todos, &alice, title, due_at).await;
+    }
+
+    let sort = TodoSortField::DueAt;
+    let mut expected = todos.list(alice.clone()).await.unwrap();
+    expected.sort_by(|a, b| sort.key_of(a).cmp(&sort.key_of(b)).then(a.id.cmp(&b.id)));
+    let expected: Vec<_> = expected.into_iter().map(|t| t.id).collect();
+
+    let ascending = walk(todos, &alice, sort, SortDirection::Asc).await;
+    assert_eq!(
+        titles(&ascending),
+        [
+            "earlier", "noon", "noon", "later", "undated", "undated", "undated"
+        ]
+    );
+    assert_eq!(
+        ascending.into_iter().map(|t| t.id).collect::<Vec<_>>(),
+        expected
+    );
+
+    let descending = walk(todos, &alice, sort, SortDirection::Desc).await;
+    assert_eq!(
+        descending
+            .into_iter()
+            .rev()
+            .map(|t| t.id)
+            .collect::<Vec<_>>(),
+        expected
+    );
+}
+
+async fn query_filters_by_due_date_and_overdue(
+    todos: &impl TodosRepository,
+    users: &impl UsersRepository,
+) {
+    let (alice, _) = register(users, "alice").await;
+    let now = Utc::now();
+    let hour = Duration::hours(1);
+    add_todo_due_at(todos, &alice, "past", Some(now - hour)).await;
+    add_todo_due_at(todos, &alice, "future", Some(now + hour)).await;
+    add_todo_due_at(todos, &alice, "undated", None).await;
+    let done = add_todo_due_at(todos, &alice, "done", Some(now - hour)).await;
+    todos.to_completed(alice.clone(), done).await.unwrap();
+
+    let query = |query: TodoQueryEntity| {
+        todos.query(
+            alice.clone(),
+            TodoQueryEntity {
+                limit: 100,
+                ..query
+            },
+        )
+    };
+
+    let overdue = query(TodoQueryEntity {
+        overdue: Some(true),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(titles(&overdue), ["past"]);
+    let not_overdue = query(TodoQueryEntity {
+        overdue: Some(false),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(titles(&not_overdue), ["future", "undated", "done"]);
+
+    // Undated todos fall outside every range.
+    let due_soon = query(TodoQueryEntity {
+        due_after: Some(now),
+        due_before: Some(now + hour * 2),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(titles(&due_soon), ["future"]);
+    let due_earlier = query(TodoQueryEntity {
+        due_before: Some(now),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(titles(&due_earlier), ["past", "done"]);
+}
+
+async fn due_dates_are_stored_as_utc_instants(
+    todos: &impl TodosRepository,
+    users: &impl UsersRepository,
+) {
+    let (alice, _) = register(users, "alice").await;
+    let kolkata = FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+    let due_at = kolkata
+        .with_ymd_and_hms(2030, 1, 1, 9, 0, 0)
+        .unwrap()
+        .with_timezone(&Utc);
+    let id = add_todo_due_at(todos, &alice, "dated", Some(due_at)).await;
+
+    let todo = todos.get(alice.clone(), id).await.unwrap();
+    assert_eq!(
+        todo.due_at,
+        Some(Utc.with_ymd_and_hms(2030, 1, 1, 3, 30, 0).unwrap())
+    );
+
+    // The stored value compares as the same instant: included from it, excluded before it.
+    let query = |due_after, due_before| {
+        todos.query(
+            alice.clone(),
+            TodoQueryEntity {
+                due_after,
+                due_before,
+                limit: 100,
+                ..Default::default()
+            },
+        )
+    };
+    assert_eq!(titles(&query(Some(due_at), None).await.unwrap()), ["dated"]);
+    assert!(query(None, Some(due_at)).await.unwrap().is_empty());
+}
+
+// Actors are random ids, so events other tests wrote to a shared database never match.
+async fn add_audit_event(audit: &impl AuditRepository, actor_id: &str, event: AuditEventKind) {
+    audit
+        .add(AddAuditEventEntity {
+            actor_id: Some(actor_id.to_string()),
+            ip_address: Some("203.0.113.7".to_string()),
+            details: Some(json!({ "method": "password" })),
+            ..AddAuditEventEntity::new(event)
+        })
+        .await
+        .unwrap();
+}
+
+fn audit_query(actor_id: &str) -> AuditQueryEntity {
+    AuditQueryEntity {
+        event: None,
+        actor_id: Some(actor_id.to_string()),
+        created_after: None,
+        created_before: None,
+        limit: 50,
+    }
+}
+
+async fn audit_events_are_listed_newest_first(audit: &impl AuditRepository) {
+    let actor = Uuid::new_v4().to_string();
+    for event in [
+        AuditEventKind::LoginFailed,
+        AuditEventKind::LoginSucceeded,
+        AuditEventKind::Logout,
+    ] {
+        add_audit_event(audit, &actor, event).await;
+    }
+
+    let events = audit.query(audit_query(&actor)).await.unwrap();
+    assert_eq!(
+        events.iter().map(|e| e.event).collect::<Vec<_>>(),
+        [
+            AuditEventKind::Logout,
+            AuditEventKind::LoginSucceeded,
+            AuditEventKind::LoginFailed,
+        ]
+    );
+    assert_eq!(events[0].ip_address.as_deref(), Some("203.0.113.7"));
+    assert_eq!(events[0].details, Some(json!({ "method": "password" })));
+
+    let newest = audit
+        .query(AuditQueryEntity {
+            limit: 2,
+            ..audit_query(&actor)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        newest.iter().map(|e| e.event).collect::<Vec<_>>(),
+        [AuditEventKind::Logout, AuditEventKind::LoginSucceeded]
+    );
+}
+
+async fn audit_events_filter_by_kind_and_time(audit: &impl AuditRepository) {
+    let actor = Uuid::new_v4().to_string();
+    add_audit_event(audit, &actor, AuditEventKind::LoginFailed).await;
+    // Apart by more than any backend's timestamp precision.
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let between = Utc::now().naive_utc();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    add_audit_event(audit, &actor, AuditEventKind::LoginSucceeded).await;
+
+    let failed = audit
+        .query(AuditQueryEntity {
+            event: Some(AuditEventKind::LoginFailed),
+            ..audit_query(&actor)
+        })
+        .await
+        .unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].event, AuditEventKind::LoginFailed);
+
+    let after = audit
+        .query(AuditQueryEntity {
+            created_after: Some(between),
+            ..audit_query(&actor)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        after.iter().map(|e| e.event).collect::<Vec<_>>(),
+        [AuditEventKind::LoginSucceeded]
+    );
+
+    let before = audit
+        .query(AuditQueryEntity {
+            created_before: Some(between),
+            ..audit_query(&actor)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        before.iter().map(|e| e.event).collect::<Vec<_>>(),
+        [AuditEventKind::LoginFailed]
+    );
+}
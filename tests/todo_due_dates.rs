@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Days, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use todos_api::{
+    application::usecases::todos::TodosUseCase,
+    domain::{
+        entities::users::{RegisterUserEntity, Role, UpdateUserEntity},
+        repositories::users::UsersRepository,
+        value_objects::todos::{AddTodoModel, TodoViewModel},
+    },
+    infrastructure::app_state::repositories::{todos::TodosAppState, users::UsersAppState},
+};
+
+// Kolkata is UTC+05:30 without daylight saving, so the local day never lines up with the
+// UTC one and the boundaries below don't move during the year.
+const TIMEZONE: Tz = chrono_tz::Asia::Kolkata;
+
+struct Fixture {
+    todos: TodosUseCase<TodosAppState, UsersAppState>,
+    user_id: String,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        let todos_repository = TodosAppState::new();
+        let users_repository = UsersAppState::new(&todos_repository);
+
+        users_repository
+            .register(RegisterUserEntity {
+                username: "alice".to_string(),
+                password_hash: String::new(),
+                role: Role::User,
+                email: None,
+                verified: true,
+            })
+            .await
+            .unwrap();
+        let user_id = users_repository
+            .find_by_username("alice".to_string())
+            .await
+            .unwrap()
+            .unwrap()
+            .id;
+        users_repository
+            .update(
+                user_id.clone(),
+                UpdateUserEntity {
+                    username: None,
+                    display_name: None,
+                    timezone: Some(TIMEZONE.name().to_string()),
+                    locale: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        Self {
+            todos: TodosUseCase::new(Arc::new(todos_repository), Arc::new(users_repository)),
+            user_id,
+        }
+    }
+
+    async fn add(&self, title: &str, due_at: Option<DateTime<Utc>>) -> String {
+        self.todos
+            .add(
+                self.user_id.clone(),
+                AddTodoModel {
+                    title: title.to_string(),
+                    due_at,
+                },
+            )
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn today(&self) -> Vec<String> {
+        let page = self
+            .todos
+            .today(self.user_id.clone(), TodoViewModel::default())
+            .await
+            .unwrap();
+
+        page.data.into_iter().map(|t| t.title).collect()
+    }
+
+    async fn upcoming(&self) -> Vec<String> {
+        let page = self
+            .todos
+            .upcoming(self.user_id.clone(), TodoViewModel::default())
+            .await
+            .unwrap();
+
+        page.data.into_iter().map(|t| t.title).collect()
+    }
+}
+
+// Start of the local day `days` after today, in UTC.
+fn local_midnight(days: u64) -> DateTime<Utc> {
+    let today = Utc::now().with_timezone(&TIMEZONE).date_naive();
+    let date = today.checked_add_days(Days::new(days)).unwrap();
+
+    TIMEZONE
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[tokio::test]
+async fn today_covers_the_local_day_only() {
+    let fixture = Fixture::new().await;
+    let second = Duration::seconds(1);
+
+    fixture
+        .add("yesterday-last", Some(local_midnight(0) - second))
+        .await;
+    fixture.add("today-first", Some(local_midnight(0))).await;
+    fixture
+        .add("today-last", Some(local_midnight(1) - second))
+        .await;
+    fixture.add("tomorrow-first", Some(local_midnight(1))).await;
+    fixture.add("no-due-date", None).await;
+
+    assert_eq!(fixture.today().await, ["today-first", "today-last"]);
+}
+
+#[tokio::test]
+async fn upcoming_covers_the_next_seven_local_days() {
+    let fixture = Fixture::new().await;
+    let second = Duration::seconds(1);
+
+    fixture
+        .add("today-last", Some(local_midnight(1) - second))
+        .await;
+    fixture.add("tomorrow-first", Some(local_midnight(1))).await;
+    fixture
+        .add("week-last", Some(local_midnight(8) - second))
+        .await;
+    fixture.add("week-after", Some(local_midnight(8))).await;
+    fixture.add("no-due-date", None).await;
+
+    assert_eq!(fixture.upcoming().await, ["tomorrow-first", "week-last"]);
+}
+
+#[tokio::test]
+async fn due_dates_serialize_as_utc() {
+    let fixture = Fixture::new().await;
+    let due_at = TIMEZONE
+        .with_ymd_and_hms(2030, 1, 1, 9, 0, 0)
+        .unwrap()
+        .with_timezone(&Utc);
+
+    let id = fixture.add("dated", Some(due_at)).await;
+    let todo = fixture
+        .todos
+        .get(fixture.user_id.clone(), id)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&todo).unwrap()["due_at"],
+        "2030-01-01T03:30:00Z"
+    );
+}